@@ -0,0 +1,28 @@
+//! 构建信息：git 提交号及构建时间，供 `/info` 端点输出
+
+use std::process::Command;
+
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+fn main() {
+    // 没有 .git 目录时（如 Docker 构建）可通过环境变量传入
+    let commit = std::env::var("GIT_COMMIT").ok().or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "--short", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+    println!(
+        "cargo:rustc-env=GIT_COMMIT={}",
+        commit.unwrap_or_else(|| "unknown".to_string())
+    );
+    let build_time = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_default();
+    println!("cargo:rustc-env=BUILD_TIME={build_time}");
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs");
+}
@@ -0,0 +1,363 @@
+//! 命令行子命令：启动服务、数据库迁移、管理员账号维护、导出 OpenAPI 文档、检查配置
+
+use std::{collections::HashMap, env, fs, io, path::PathBuf};
+
+use common::{AppError, AppResult};
+use framework::{
+    client_ip::ClientIpTool, config::Setting, db::DBPool, ip_location::IpLocationTool,
+    jwt::JwtConfig, migrate, oidc::OidcTool, password::PasswordPolicy,
+};
+use sqlx::PgPool;
+use system::{
+    role::service as role_service,
+    user::{model::SysUserAddDTO, service as user_service},
+};
+use tracing::{info, warn};
+
+pub const USAGE: &str = "用法: app [命令]
+
+命令:
+  serve                                   启动服务（默认）
+  migrate up                              执行未执行的数据库迁移
+  migrate down [--to <版本>]              回滚最近一次迁移，或回滚到指定版本（0 表示全部）
+  migrate status                          查看迁移执行状态
+  user create-admin --phone <手机号> [--nick-name <昵称>] [--password <密码>]
+                                          创建超级管理员，手机号同时作为登录账号
+  user reset-password --user-name <账号> [--password <密码>]
+                                          重置用户密码
+  openapi export [--output <文件>]        导出 OpenAPI 文档，默认 openapi.json
+  config check [--mode <运行模式>]        加载并校验 config/<运行模式> 配置，默认取 RUN_MODE
+  help                                    显示帮助
+
+建议省略 --password，从标准输入读取密码；通过选项传入的密码会留在命令历史和进程列表中。";
+
+/// 超级管理员角色的权限字符串，见初始数据迁移
+const ADMIN_ROLE_KEY: &str = "admin";
+/// 拥有全部权限的超级管理员用户ID
+const SUPER_ADMIN_USER_ID: i32 = 1;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Serve,
+    Migrate(MigrateAction),
+    CreateAdmin {
+        phone: String,
+        nick_name: String,
+        password: Option<String>,
+    },
+    ResetPassword {
+        user_name: String,
+        password: Option<String>,
+    },
+    OpenapiExport {
+        output: PathBuf,
+    },
+    ConfigCheck {
+        mode: String,
+    },
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MigrateAction {
+    Up,
+    /// 回滚到指定版本，为空时回滚最近一次迁移
+    Down {
+        to: Option<i64>,
+    },
+    Status,
+}
+
+impl Command {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> AppResult<Self> {
+        let args: Vec<String> = args.into_iter().collect();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let command = match args.as_slice() {
+            [] | ["serve"] => Command::Serve,
+            ["help" | "-h" | "--help", ..] => Command::Help,
+            ["migrate", "up"] => Command::Migrate(MigrateAction::Up),
+            ["migrate", "status"] => Command::Migrate(MigrateAction::Status),
+            ["migrate", "down", rest @ ..] => {
+                let mut opts = options(rest, &["--to"])?;
+                let to = opts
+                    .remove("--to")
+                    .map(|v| {
+                        v.parse::<i64>()
+                            .map_err(|_| AppError::Other(format!("无效的迁移版本: {v}")))
+                    })
+                    .transpose()?;
+                Command::Migrate(MigrateAction::Down { to })
+            }
+            ["user", "create-admin", rest @ ..] => {
+                let mut opts = options(rest, &["--phone", "--nick-name", "--password"])?;
+                Command::CreateAdmin {
+                    phone: required(&mut opts, "--phone")?,
+                    nick_name: opts
+                        .remove("--nick-name")
+                        .unwrap_or("超级管理员")
+                        .to_string(),
+                    password: opts.remove("--password").map(str::to_string),
+                }
+            }
+            ["user", "reset-password", rest @ ..] => {
+                let mut opts = options(rest, &["--user-name", "--password"])?;
+                Command::ResetPassword {
+                    user_name: required(&mut opts, "--user-name")?,
+                    password: opts.remove("--password").map(str::to_string),
+                }
+            }
+            ["openapi", "export", rest @ ..] => {
+                let mut opts = options(rest, &["--output"])?;
+                Command::OpenapiExport {
+                    output: opts.remove("--output").unwrap_or("openapi.json").into(),
+                }
+            }
+            ["config", "check", rest @ ..] => {
+                let mut opts = options(rest, &["--mode"])?;
+                Command::ConfigCheck {
+                    mode: match opts.remove("--mode") {
+                        Some(mode) => mode.to_string(),
+                        None => env::var("RUN_MODE").unwrap_or_else(|_| "dev".into()),
+                    },
+                }
+            }
+            _ => {
+                return Err(AppError::Other(format!(
+                    "未知命令: {}\n\n{USAGE}",
+                    args.join(" ")
+                )));
+            }
+        };
+        Ok(command)
+    }
+}
+
+/// 解析 `--key value` 或 `--key=value` 形式的选项
+fn options<'a>(args: &[&'a str], allowed: &[&str]) -> AppResult<HashMap<&'a str, &'a str>> {
+    let mut opts = HashMap::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (key, value) = match arg.split_once('=') {
+            Some((key, value)) => (key, Some(value)),
+            None => (*arg, None),
+        };
+        if !allowed.contains(&key) {
+            return Err(AppError::Other(format!("未知选项: {key}\n\n{USAGE}")));
+        }
+        let value = value
+            .or_else(|| iter.next().copied())
+            .ok_or_else(|| AppError::Other(format!("选项 {key} 缺少参数")))?;
+        opts.insert(key, value);
+    }
+    Ok(opts)
+}
+
+fn required(opts: &mut HashMap<&str, &str>, key: &str) -> AppResult<String> {
+    opts.remove(key)
+        .map(str::to_string)
+        .ok_or_else(|| AppError::Other(format!("缺少选项 {key}\n\n{USAGE}")))
+}
+
+/// 未通过选项指定密码时从标准输入读取
+fn read_password(password: Option<String>) -> AppResult<String> {
+    if let Some(password) = password {
+        warn!(
+            "[CLI] --password is visible in shell history and the process list, omit it to read the password from stdin"
+        );
+        return Ok(password);
+    }
+    eprint!("请输入密码: ");
+    let mut line = String::new();
+    io::stdin()
+        .read_line(&mut line)
+        .map_err(|e| AppError::Other(format!("读取密码失败: {e}")))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// 执行数据库迁移命令
+pub async fn migrate(setting: &Setting, action: MigrateAction) -> AppResult<()> {
+    let pool = DBPool::connect(&setting.database.get_url()).await?;
+    match action {
+        MigrateAction::Up => migrate::run(&pool).await?,
+        MigrateAction::Down { to } => {
+            let target = match to {
+                Some(to) => to,
+                // 最近一次迁移之前的版本
+                None => {
+                    let applied: Vec<i64> = migrate::status(&pool)
+                        .await?
+                        .into_iter()
+                        .filter(|m| m.applied)
+                        .map(|m| m.version)
+                        .collect();
+                    match applied.as_slice() {
+                        [] => {
+                            info!("[MIGRATE] No applied migration to revert");
+                            return Ok(());
+                        }
+                        [.., previous, _] => *previous,
+                        [_] => 0,
+                    }
+                }
+            };
+            migrate::undo(&pool, target).await?;
+        }
+        MigrateAction::Status => {
+            for m in migrate::status(&pool).await? {
+                let state = if m.applied { "已执行" } else { "未执行" };
+                println!("{}  {}  {}", m.version, state, m.description);
+            }
+        }
+    }
+    pool.close().await;
+    Ok(())
+}
+
+/// 创建超级管理员
+///
+/// 只有用户ID为 1 的账号跳过权限校验，该ID已被占用时拒绝创建。
+pub async fn create_admin(
+    db: &PgPool,
+    policy: &PasswordPolicy,
+    phone: String,
+    nick_name: String,
+    password: Option<String>,
+) -> AppResult<()> {
+    if user_service::select_user_by_username(db, &phone)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Other(format!("用户 {phone} 已存在")));
+    }
+    let taken = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM sys_user WHERE user_id = $1) AS "exists!""#,
+        SUPER_ADMIN_USER_ID
+    )
+    .fetch_one(db)
+    .await?;
+    if taken {
+        return Err(AppError::Other(format!(
+            "用户ID {SUPER_ADMIN_USER_ID} 已被占用，新账号不会拥有全部权限；请使用 user reset-password 重置已有超级管理员的密码"
+        )));
+    }
+    let role_id = role_service::select_role_id_by_key(db, ADMIN_ROLE_KEY)
+        .await?
+        .ok_or(AppError::Other(
+            "超级管理员角色不存在，请先执行数据库迁移".to_string(),
+        ))?;
+    let password = read_password(password)?;
+    user_service::add_user(
+        db,
+        policy,
+        SysUserAddDTO {
+            nick_name,
+            user_type: Some("00".to_string()),
+            email: None,
+            phone_number: phone.clone(),
+            avatar: None,
+            password,
+            status: Some("0".to_string()),
+            remark: Some("命令行创建的超级管理员".to_string()),
+            dept_id: None,
+            role_ids: Some(vec![role_id]),
+            post_ids: None,
+        },
+    )
+    .await?;
+    let user = user_service::select_user_by_username(db, &phone)
+        .await?
+        .ok_or(AppError::RecordNotFound)?;
+    if user.user_id != SUPER_ADMIN_USER_ID {
+        return Err(AppError::Other(format!(
+            "用户 {phone} 已创建，但用户ID为 {}，只有用户ID {SUPER_ADMIN_USER_ID} 拥有全部权限；请停用该账号后检查 sys_user 的主键序列",
+            user.user_id
+        )));
+    }
+    println!("已创建超级管理员 {phone}（用户ID {}）", user.user_id);
+    Ok(())
+}
+
+/// 重置用户密码
+pub async fn reset_password(
+    db: &PgPool,
+    policy: &PasswordPolicy,
+    user_name: String,
+    password: Option<String>,
+) -> AppResult<()> {
+    let user = user_service::select_user_by_username(db, &user_name)
+        .await?
+        .ok_or_else(|| AppError::Other(format!("用户 {user_name} 不存在")))?;
+    let password = read_password(password)?;
+    user_service::reset_user_password(db, policy, user.user_id, &password).await?;
+    println!("已重置用户 {user_name} 的密码");
+    Ok(())
+}
+
+/// 导出合并后的 OpenAPI 文档
+pub fn openapi_export(output: &PathBuf) -> AppResult<()> {
+    let doc = app::openapi().to_pretty_json()?;
+    if let Some(dir) = output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .map_err(|e| AppError::Other(format!("创建目录 {} 失败: {e}", dir.display())))?;
+    }
+    fs::write(output, doc)
+        .map_err(|e| AppError::Other(format!("写入 {} 失败: {e}", output.display())))?;
+    println!("已导出 OpenAPI 文档到 {}", output.display());
+    Ok(())
+}
+
+/// 加载并校验配置，只构建各工具，不连接数据库
+pub fn config_check(mode: &str) -> AppResult<()> {
+    let setting = Setting::load(mode)?;
+    JwtConfig::try_from(&setting.jwt)?;
+    PasswordPolicy::new(setting.password)?;
+    ClientIpTool::new(setting.proxy)?;
+    IpLocationTool::new(setting.ip_location)?;
+    OidcTool::new(setting.oidc)?;
+    println!("配置检查通过: config/{mode}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Command, MigrateAction};
+
+    fn parse(args: &str) -> Result<Command, String> {
+        Command::parse(args.split_whitespace().map(str::to_string)).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(parse(""), Ok(Command::Serve));
+        assert_eq!(parse("--help"), Ok(Command::Help));
+        assert_eq!(
+            parse("migrate down"),
+            Ok(Command::Migrate(MigrateAction::Down { to: None }))
+        );
+        assert_eq!(
+            parse("migrate down --to=20251018000001"),
+            Ok(Command::Migrate(MigrateAction::Down {
+                to: Some(20251018000001)
+            }))
+        );
+        assert_eq!(
+            parse("user create-admin --phone 13800000000 --password Xk9#mPq2z"),
+            Ok(Command::CreateAdmin {
+                phone: "13800000000".to_string(),
+                nick_name: "超级管理员".to_string(),
+                password: Some("Xk9#mPq2z".to_string()),
+            })
+        );
+        assert_eq!(
+            parse("openapi export --output doc/api.json"),
+            Ok(Command::OpenapiExport {
+                output: "doc/api.json".into()
+            })
+        );
+        assert!(parse("user create-admin").is_err());
+        assert!(parse("user reset-password --user-name").is_err());
+        assert!(parse("migrate down --to abc").is_err());
+        assert!(parse("config check --unknown x").is_err());
+        assert!(parse("deploy").is_err());
+    }
+}
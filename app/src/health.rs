@@ -0,0 +1,146 @@
+//! 存活、就绪探针及构建信息，不经过认证、操作日志等中间件
+
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use common::response::ResponseResult;
+use framework::{
+    config::Upload,
+    state::{Config, Db},
+};
+use salvo::{Response, Router, Writer, handler, http::StatusCode, writing::Json};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// 数据库检查超时时间
+const DB_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 单项检查结果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Check {
+    up: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl Check {
+    fn from_result(result: Result<(), String>) -> Self {
+        Self {
+            up: result.is_ok(),
+            detail: result.err(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Readiness {
+    database: Check,
+    /// 数据库耗时，单位：毫秒
+    database_latency_ms: Option<u128>,
+    /// 已执行的最新迁移版本
+    migration_version: Option<i64>,
+    jwt: Check,
+    upload: Check,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildInfo {
+    name: &'static str,
+    version: &'static str,
+    git_commit: &'static str,
+    build_time: &'static str,
+}
+
+pub fn init_router() -> Router {
+    Router::new()
+        .push(Router::with_path("health/live").get(live))
+        .push(Router::with_path("health/ready").get(ready))
+        .push(Router::with_path("info").get(info))
+}
+
+/// 存活探针，进程能处理请求即可
+#[handler]
+pub async fn live() -> ResponseResult<()> {
+    ResponseResult::success_msg("UP")
+}
+
+/// 就绪探针，所有依赖可用时返回 200，否则返回 503
+#[handler]
+pub async fn ready(db: Db, config: Config, res: &mut Response) {
+    let start = Instant::now();
+    let database = Check::from_result(check_database(&db).await);
+    let database_latency_ms = database.up.then(|| start.elapsed().as_millis());
+    let readiness = Readiness {
+        migration_version: if database.up {
+            migration_version(&db).await
+        } else {
+            None
+        },
+        database,
+        database_latency_ms,
+        // JWT 密钥在启动时加载，加载失败则无法启动
+        jwt: Check::from_result(Ok(())),
+        upload: Check::from_result(check_upload_dir(&config.upload)),
+    };
+    let up = readiness.database.up && readiness.jwt.up && readiness.upload.up;
+    let mut result = ResponseResult::success_with_msg(if up { "UP" } else { "DOWN" }, readiness);
+    if !up {
+        result.code = StatusCode::SERVICE_UNAVAILABLE.as_u16();
+        res.status_code(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    res.render(Json(result));
+}
+
+/// 构建信息
+#[handler]
+pub async fn info() -> ResponseResult<BuildInfo> {
+    ResponseResult::success(BuildInfo {
+        name: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit: env!("GIT_COMMIT"),
+        build_time: env!("BUILD_TIME"),
+    })
+}
+
+async fn check_database(pool: &PgPool) -> Result<(), String> {
+    match tokio::time::timeout(DB_TIMEOUT, sqlx::query("SELECT 1").execute(pool)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("查询超时（{}秒）", DB_TIMEOUT.as_secs())),
+    }
+}
+
+/// 迁移表不存在时返回 `None`
+async fn migration_version(pool: &PgPool) -> Option<i64> {
+    let query = async {
+        let exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(pool)
+            .await?;
+        if !exists {
+            return Ok(None);
+        }
+        sqlx::query_scalar("SELECT max(version) FROM _sqlx_migrations WHERE success")
+            .fetch_one(pool)
+            .await
+    };
+    tokio::time::timeout(DB_TIMEOUT, query)
+        .await
+        .ok()?
+        .unwrap_or(None)
+}
+
+/// 上传目录可写
+fn check_upload_dir(upload: &Upload) -> Result<(), String> {
+    let dir = Path::new(&upload.path);
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {}", upload.path, e))?;
+    let probe = dir.join(".health");
+    fs::write(&probe, b"ok").map_err(|e| format!("{}: {}", upload.path, e))?;
+    fs::remove_file(&probe).ok();
+    Ok(())
+}
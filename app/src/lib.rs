@@ -1,26 +1,194 @@
-use framework::midddleware::auth;
+mod health;
+
+use framework::{
+    jwks::jwks,
+    metrics::metrics,
+    midddleware::{auth, cors, http_metrics, preflight, rate_limit, request_id, security_headers},
+    state::{AppState, inject},
+};
 use salvo::{
     Router,
     oapi::OpenApi,
     prelude::{StaticFile, SwaggerUi},
 };
 
-pub fn init_router() -> Router {
-    let router = Router::new()
+/// 根路由，应用状态注入到所有请求
+pub fn init_router(state: AppState) -> Router {
+    let router = api_router();
+    let doc = openapi_doc(&router);
+
+    let static_router = Router::with_path("admin").get(StaticFile::new("static/index.html"));
+
+    let router = router
+        .push(static_router)
+        //任意路径的预检请求
+        .push(Router::with_path("{**rest}").options(preflight))
+        .unshift(doc.into_router("/api-doc/openapi.json"))
+        .unshift(SwaggerUi::new("/api-doc/openapi.json").into_router("/swagger-ui"));
+
+    Router::new()
+        .hoop(inject(state))
+        //探针及构建信息，不经过认证、操作日志、限流等中间件
+        .push(health::init_router())
+        .push(router)
+}
+
+/// 合并所有业务路由后的 OpenAPI 文档
+pub fn openapi() -> OpenApi {
+    openapi_doc(&api_router())
+}
+
+fn openapi_doc(router: &Router) -> OpenApi {
+    OpenApi::new("test api", "0.0.1").merge_router(router)
+}
+
+/// 业务路由及其中间件
+fn api_router() -> Router {
+    Router::new()
+        .hoop(request_id)
+        .hoop(http_metrics)
+        .hoop(security_headers)
+        .hoop(cors)
+        .hoop(rate_limit)
         .hoop(system::handle::oper_log_middleware)
         .push(system::init_router())
+        //公开的 JWKS，供其他服务验签
+        .push(Router::with_path(".well-known/jwks.json").get(jwks))
+        //Prometheus 指标
+        .push(Router::with_path("metrics").get(metrics))
         .push(
             Router::new()
                 //需要认证的路由
                 .hoop(auth)
                 .push(monitor::init_router()),
-        );
-    let doc = OpenApi::new("test api", "0.0.1").merge_router(&router);
+        )
+}
 
-    let static_router = Router::with_path("admin").get(StaticFile::new("static/index.html"));
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
 
-    router
-        .push(static_router)
-        .unshift(doc.into_router("/api-doc/openapi.json"))
-        .unshift(SwaggerUi::new("/api-doc/openapi.json").into_router("/swagger-ui"))
+    use async_trait::async_trait;
+    use common::{AppResult, constants::Permission, models::sys_user_online::SysUserOnline};
+    use config::{Config, File, FileFormat};
+    use framework::{
+        Setting,
+        jwt::{JwtAuthUtil, JwtConfig, TokenType},
+        permission::{PermTool, PermissionLoader},
+        session::SessionCache,
+        state::AppState,
+    };
+    use salvo::{
+        Service,
+        http::StatusCode,
+        test::{ResponseExt, TestClient},
+    };
+    use serde_json::Value;
+    use sqlx::{PgPool, postgres::PgPoolOptions};
+    use time::OffsetDateTime;
+
+    struct FakeLoader(Vec<String>);
+
+    #[async_trait]
+    impl PermissionLoader for FakeLoader {
+        async fn load(&self, _user_id: i32) -> AppResult<Vec<String>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    /// 构建一个实例的应用状态，并记录一个在线会话，返回状态及该会话的访问令牌
+    async fn state(
+        db: PgPool,
+        name: &str,
+        perms: Vec<String>,
+    ) -> anyhow::Result<(AppState, String)> {
+        let jwt = JwtAuthUtil::new(JwtConfig::new(
+            format!("secret-{name}"),
+            5,
+            10,
+            name.to_string(),
+        ));
+        let mut setting: Setting = Config::builder()
+            .add_source(File::from_str(
+                include_str!("../../config/test.toml"),
+                FileFormat::Toml,
+            ))
+            .build()?
+            .try_deserialize()?;
+        setting.upload.path = std::env::temp_dir()
+            .join(format!("uploads-{name}"))
+            .to_string_lossy()
+            .into_owned();
+
+        let sid = format!("sid-{name}");
+        let token = jwt.generate_token(2, &sid, TokenType::Access)?;
+        let sessions = SessionCache::new(Duration::from_secs(60), setting.session.max_capacity);
+        sessions
+            .insert(SysUserOnline {
+                token_id: sid,
+                user_id: 2,
+                user_name: name.to_string(),
+                tick_name: name.to_string(),
+                ipaddr: None,
+                login_location: None,
+                browser: None,
+                os: None,
+                login_time: OffsetDateTime::now_utc(),
+                token: token.clone(),
+                refresh_token: String::new(),
+            })
+            .await;
+        let state = AppState::new(db, jwt, setting)
+            .with(sessions)
+            .with(PermTool::new(FakeLoader(perms)));
+        Ok((framework::init_state(state)?, token))
+    }
+
+    async fn online_page(service: &Service, token: &str) -> (Option<StatusCode>, Value) {
+        let mut res = TestClient::post("http://127.0.0.1/monitor/online/page")
+            .bearer_auth(token)
+            .json(&serde_json::json!({}))
+            .send(service)
+            .await;
+        let body = res.take_json::<Value>().await.unwrap_or_default();
+        (res.status_code, body)
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn state_per_router_test(db: PgPool) -> anyhow::Result<()> {
+        // 同一进程中的两个路由实例，各自注入不同的应用状态
+        let (state_a, token_a) =
+            state(db, "a", vec![Permission::OnlineList.as_ref().to_string()]).await?;
+        let unreachable = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("postgres://postgres@127.0.0.1:1/test")?;
+        let (state_b, token_b) = state(unreachable, "b", Vec::new()).await?;
+        let a = Service::new(super::init_router(state_a));
+        let b = Service::new(super::init_router(state_b));
+
+        // Db：只有 a 的数据库可用
+        let res = TestClient::get("http://127.0.0.1/health/ready")
+            .send(&a)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        let res = TestClient::get("http://127.0.0.1/health/ready")
+            .send(&b)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::SERVICE_UNAVAILABLE));
+
+        // Jwt：令牌只能在签发它的实例使用
+        let (status, _) = online_page(&b, &token_a).await;
+        assert_eq!(status, Some(StatusCode::UNAUTHORIZED));
+        let (status, _) = online_page(&a, &token_b).await;
+        assert_eq!(status, Some(StatusCode::UNAUTHORIZED));
+
+        // Ext：权限工具、在线会话按实例区分
+        let (status, body) = online_page(&a, &token_a).await;
+        assert_eq!(status, Some(StatusCode::OK));
+        assert_eq!(body["data"]["total"], 1);
+        assert_eq!(body["data"]["items"][0]["userName"], "a");
+        let (status, _) = online_page(&b, &token_b).await;
+        assert_eq!(status, Some(StatusCode::FORBIDDEN));
+        Ok(())
+    }
 }
@@ -1,28 +1,140 @@
+mod cli;
+
+use std::{path::Path, time::Duration};
+
 use common::AppResult;
-use framework::{config, db::DBPool, jwt::JWTTool, log};
+use framework::{
+    background::BackgroundTasks,
+    config,
+    db::DBPool,
+    jwt::JwtAuthUtil,
+    log::{self, LogTool},
+    password::PasswordPolicy,
+    session::SessionCache,
+    state::AppState,
+};
 use salvo::prelude::*;
-use system::file::UploadTool;
+use sqlx::PgPool;
+use system::handle::replay_audit_spill;
+use tracing::info;
+
+use crate::cli::Command;
 
 #[tokio::main]
-async fn main() -> AppResult<()> {
-    run().await
+async fn main() {
+    let result = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => run(command).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
 }
 
-async fn run() -> AppResult<()> {
+async fn run(command: Command) -> AppResult<()> {
+    // Commands that need neither config nor database
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Command::ConfigCheck { mode } => return log::bootstrap(|| cli::config_check(&mode)),
+        Command::OpenapiExport { output } => return cli::openapi_export(&output),
+        _ => {}
+    }
+    // Initialize config subsystem, logging to stderr until the configured logger is ready
+    let setting = log::bootstrap(config::Setting::init)?;
     // Initialize logging subsystem
-    log::init_tracing();
-    // Initialize config subsystem
-    let setting = config::Setting::init()?;
-    // Initialize jwt auth util
-    JWTTool::init((&setting.jwt).into());
-    UploadTool::init(setting.upload);
-    // Initialize jwt auth util
-    DBPool::inint(&setting.database.get_url()).await?;
+    let log_tool = log::init_tracing(&setting.log)?;
+    match command {
+        Command::Migrate(action) => cli::migrate(&setting, action).await,
+        Command::CreateAdmin {
+            phone,
+            nick_name,
+            password,
+        } => {
+            let (db, policy) = init_cli_db(setting).await?;
+            cli::create_admin(&db, &policy, phone, nick_name, password).await
+        }
+        Command::ResetPassword {
+            user_name,
+            password,
+        } => {
+            let (db, policy) = init_cli_db(setting).await?;
+            cli::reset_password(&db, &policy, user_name, password).await
+        }
+        _ => serve(setting, log_tool).await,
+    }
+}
+
+/// Initialize what the user commands need: password policy and database pool
+async fn init_cli_db(setting: config::Setting) -> AppResult<(PgPool, PasswordPolicy)> {
+    let policy = PasswordPolicy::new(setting.password)?;
+    let db = DBPool::inint(&setting.database.get_url(), setting.database.auto_migrate).await?;
+    Ok((db, policy))
+}
+
+async fn serve(setting: config::Setting, log_tool: LogTool) -> AppResult<()> {
+    // Initialize database pool, refusing to start when the schema is behind
+    let pool = DBPool::inint(&setting.database.get_url(), setting.database.auto_migrate).await?;
+    // Build application state shared by all handlers,
+    // online sessions live as long as the refresh token
+    let jwt = JwtAuthUtil::new((&setting.jwt).try_into()?);
+    let sessions = SessionCache::new(
+        Duration::from_secs(setting.jwt.ref_expiration_hour as u64 * 60),
+        setting.session.max_capacity,
+    );
+    let state = AppState::new(pool.clone(), jwt, setting.clone())
+        .with(sessions)
+        .with(log_tool);
+    // Framework tools built from config, then the system module's own state
+    let state = system::init_state(framework::init_state(state)?)?;
+    let background = state.ext::<BackgroundTasks>()?.clone();
+    // Replay audit logs spilled by the previous shutdown
+    replay_audit_spill(&pool, &setting.shutdown.spill_path).await;
 
     let acceptor = TcpListener::new(("0.0.0.0", setting.server.port))
         .bind()
         .await;
 
-    Server::new(acceptor).serve(app::init_router()).await;
+    let drain_timeout = Duration::from_secs(setting.shutdown.drain_timeout_secs);
+    let server = Server::new(acceptor);
+    // Stop accepting connections on SIGTERM / Ctrl-C and drain in-flight requests
+    let handle = server.handle();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        info!("Shutdown signal received, draining in-flight requests");
+        handle.stop_graceful(drain_timeout);
+    });
+    server.serve(app::init_router(state)).await;
+
+    // Flush pending background audit writes, abort and spill the rest to disk
+    background
+        .drain(drain_timeout, Path::new(&setting.shutdown.spill_path))
+        .await?;
+    pool.close().await;
+    info!("Shutdown complete");
     Ok(())
 }
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
@@ -1,5 +1,8 @@
 use strum::{AsRefStr, EnumString};
 
+/// 请求编号的请求头/响应头
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
 /// # 系统权限枚举
 ///
 /// 定义了系统中所有可用的API权限点。
@@ -43,4 +46,50 @@ pub enum Permission {
     MenuEdit,
     #[strum(serialize = "system:menu:remove")]
     MenuRemove,
+
+    // 部门管理
+    #[strum(serialize = "system:dept:list")]
+    DeptList,
+    #[strum(serialize = "system:dept:query")]
+    DeptQuery,
+    #[strum(serialize = "system:dept:add")]
+    DeptAdd,
+    #[strum(serialize = "system:dept:edit")]
+    DeptEdit,
+    #[strum(serialize = "system:dept:remove")]
+    DeptRemove,
+
+    // 岗位管理
+    #[strum(serialize = "system:post:list")]
+    PostList,
+    #[strum(serialize = "system:post:query")]
+    PostQuery,
+    #[strum(serialize = "system:post:add")]
+    PostAdd,
+    #[strum(serialize = "system:post:edit")]
+    PostEdit,
+    #[strum(serialize = "system:post:remove")]
+    PostRemove,
+
+    // 字典管理
+    #[strum(serialize = "system:dict:list")]
+    DictList,
+    #[strum(serialize = "system:dict:add")]
+    DictAdd,
+
+    // 系统监控
+    #[strum(serialize = "monitor:operlog:list")]
+    OperlogList,
+    #[strum(serialize = "monitor:logininfor:list")]
+    LogininforList,
+    #[strum(serialize = "monitor:logininfor:unlock")]
+    LogininforUnlock,
+    #[strum(serialize = "monitor:online:list")]
+    OnlineList,
+    #[strum(serialize = "monitor:online:forceLogout")]
+    OnlineForceLogout,
+    #[strum(serialize = "monitor:log:query")]
+    LogLevelQuery,
+    #[strum(serialize = "monitor:log:edit")]
+    LogLevelEdit,
 }
@@ -9,7 +9,7 @@ use salvo::{
 use thiserror::Error;
 use tracing::error;
 
-use crate::response::ResponseResult;
+use crate::{constants::REQUEST_ID_HEADER, response::ResponseResult};
 
 #[derive(Debug, Error)]
 pub enum AppError {
@@ -37,6 +37,10 @@ pub enum AppError {
     TokenInvalid,
     #[error("Permission denied")]
     PermissionDenied,
+    #[error("Account is locked, retry after {0} minutes")]
+    AccountLocked(u64),
+    #[error("Too many requests, retry after {0} seconds")]
+    TooManyRequests(u64),
     #[error(transparent)]
     JsonParseError(#[from] serde_json::Error),
     #[error(transparent)]
@@ -92,6 +96,16 @@ impl Writer for AppError {
                 "认证令牌无效或已过期".to_string(),
             ),
             AppError::PermissionDenied => (StatusCode::FORBIDDEN, 403, "权限不足".to_string()),
+            AppError::AccountLocked(minutes) => (
+                StatusCode::LOCKED,
+                423,
+                format!("登录失败次数过多，账户已锁定，请{}分钟后重试", minutes),
+            ),
+            AppError::TooManyRequests(seconds) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                429,
+                format!("请求过于频繁，请{}秒后重试", seconds),
+            ),
 
             AppError::JsonParseError(e) => {
                 (StatusCode::BAD_REQUEST, 400, format!("JSON格式错误: {}", e))
@@ -101,7 +115,13 @@ impl Writer for AppError {
             }
             AppError::Other(e) => (StatusCode::INTERNAL_SERVER_ERROR, 500, e.to_owned()),
         };
-        let reponse_result = ResponseResult::<()>::error(business_code, &message);
+        let request_id = res
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let reponse_result =
+            ResponseResult::<()>::error(business_code, &message).with_request_id(request_id);
         res.status_code(http_status);
         res.render(Json(reponse_result));
     }
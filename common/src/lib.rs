@@ -9,4 +9,4 @@ pub mod utils;
 
 pub use crate::error::AppError;
 pub use crate::error::Result as AppResult;
-pub use crate::sql_builder::SqlBuilder;
+pub use crate::sql_builder::{DataScope, SqlBuilder};
@@ -1,13 +1,18 @@
+use salvo::oapi::ToSchema;
 use serde::Serialize;
 use time::OffsetDateTime;
 
+use crate::utils::time::offset;
+
 /// 在线用户信息实体，用于缓存和前端展示。
 /// 这个结构体不对应数据库表，而是内存中的数据模型。
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SysUserOnline {
-    /// 会话编号 (我们使用 JWT 的 jti)
+    /// 会话编号 (我们使用 JWT 的 sid)
     pub token_id: String,
+    /// 用户ID
+    pub user_id: i32,
     /// 用户名称
     pub user_name: String,
     pub tick_name: String,
@@ -20,11 +25,15 @@ pub struct SysUserOnline {
     /// 操作系统
     pub os: Option<String>,
     /// 登录时间 (使用带时区的时间，序列化为 "2023-07-03T10:30:00+08:00" 格式)
+    #[serde(with = "offset")]
     pub login_time: OffsetDateTime,
 
     /// 隐藏字段：存储完整的 JWT，用于实现“强退”功能。 这个字段不返回给前端，所以使用 #[serde(skip_serializing)]
     #[serde(skip_serializing)]
     pub token: String,
+    /// 隐藏字段：当前有效的刷新令牌，用于检测已轮换刷新令牌的重放
+    #[serde(skip_serializing)]
+    pub refresh_token: String,
 }
 
 #[cfg(test)]
@@ -15,6 +15,9 @@ pub struct ResponseResult<T: Serialize = ()> {
     pub msg: String,
     // 业务数据
     pub data: T,
+    /// 请求编号，仅错误响应返回，用于与服务端日志对应
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl<T: Serialize> ResponseResult<T> {
@@ -24,6 +27,7 @@ impl<T: Serialize> ResponseResult<T> {
             code: 200,
             msg: "sucess".to_string(),
             data,
+            request_id: None,
         }
     }
 
@@ -32,6 +36,7 @@ impl<T: Serialize> ResponseResult<T> {
             code: 200,
             msg: msg.to_string(),
             data,
+            request_id: None,
         }
     }
 }
@@ -43,6 +48,7 @@ impl ResponseResult<()> {
             code: 200,
             msg: msg.to_string(),
             data: (),
+            request_id: None,
         }
     }
     /// 创建一个失败的响应
@@ -52,8 +58,14 @@ impl ResponseResult<()> {
             code,
             msg: msg.to_string(),
             data: (),
+            request_id: None,
         }
     }
+
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
 }
 
 #[test]
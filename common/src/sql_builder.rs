@@ -3,9 +3,30 @@ use std::{fmt::Display, marker::PhantomData};
 
 use crate::{AppResult, page_reponse::PageReponse};
 
+/// 数据权限范围（由角色的 data_scope 计算得出）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataScope {
+    /// 全部数据
+    All,
+    /// 仅限指定部门的数据，以及本人的数据
+    Limited { user_id: i32, dept_ids: Vec<i32> },
+}
+
+impl DataScope {
+    /// 部门是否在数据权限范围内，受限范围下未指定部门视为不在范围内
+    pub fn contains_dept(&self, dept_id: Option<i32>) -> bool {
+        match self {
+            DataScope::All => true,
+            DataScope::Limited { dept_ids, .. } => {
+                dept_id.is_some_and(|dept_id| dept_ids.contains(&dept_id))
+            }
+        }
+    }
+}
+
 /// SQL查询构建器，支持条件构建和分页查询（专为PostgreSQL优化）
 pub struct SqlBuilder<'a> {
-    db: &'static Pool<Postgres>,
+    db: &'a Pool<Postgres>,
     query_builder: QueryBuilder<'a, Postgres>,
     count_builder: Option<QueryBuilder<'a, Postgres>>,
     has_where_clause: bool,
@@ -14,7 +35,7 @@ pub struct SqlBuilder<'a> {
 
 impl<'a> SqlBuilder<'a> {
     /// 创建新的SQL构建器
-    pub fn new(db: &'static Pool<Postgres>, query_sql: &str) -> Self {
+    pub fn new(db: &'a Pool<Postgres>, query_sql: &str) -> Self {
         let query_builder = QueryBuilder::new(query_sql);
 
         Self {
@@ -28,7 +49,7 @@ impl<'a> SqlBuilder<'a> {
 
     /// 创建用于分页查询的SQL构建器
     pub fn for_pagination(
-        db: &'static Pool<Postgres>,
+        db: &'a Pool<Postgres>,
         select_clauses: &str,
         table_name: &str,
         default_condition: Option<&str>,
@@ -211,6 +232,37 @@ impl<'a> SqlBuilder<'a> {
         self
     }
 
+    /// 数据权限条件：`(部门列 = ANY(部门列表) OR 用户列 = 当前用户)`，没有用户列时只按部门过滤
+    pub fn where_data_scope(
+        &mut self,
+        scope: &DataScope,
+        dept_column: &str,
+        user_column: Option<&str>,
+    ) -> &mut Self {
+        let DataScope::Limited { user_id, dept_ids } = scope else {
+            return self;
+        };
+        let keyword = if self.has_where_clause {
+            " AND "
+        } else {
+            " WHERE "
+        };
+        self.has_where_clause = true;
+        let builders = std::iter::once(&mut self.query_builder).chain(self.count_builder.as_mut());
+        for builder in builders {
+            builder.push(keyword);
+            builder.push(format!("({} = ANY(", dept_column));
+            builder.push_bind(dept_ids.clone());
+            builder.push(")");
+            if let Some(user_column) = user_column {
+                builder.push(format!(" OR {} = ", user_column));
+                builder.push_bind(*user_id);
+            }
+            builder.push(")");
+        }
+        self
+    }
+
     /// 大于等于条件
     pub fn where_ge<T>(&mut self, column: &str, value: Option<T>) -> &mut Self
     where
@@ -361,3 +413,67 @@ macro_rules! impl_not_empty {
     };
 }
 impl_not_empty!(i8, i16, i32, i64, f32, f64, bool);
+
+#[cfg(test)]
+mod test {
+    use sqlx::postgres::PgPoolOptions;
+
+    use crate::{DataScope, SqlBuilder};
+
+    #[tokio::test]
+    async fn where_data_scope_test() {
+        let db = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+        let mut sql_builder = SqlBuilder::for_pagination(&db, "*", "sys_user", None);
+        sql_builder.where_data_scope(&DataScope::All, "dept_id", Some("user_id"));
+        assert_eq!(sql_builder.quer_sql(), "select * from sys_user");
+
+        sql_builder
+            .where_data_scope(
+                &DataScope::Limited {
+                    user_id: 2,
+                    dept_ids: vec![1, 3],
+                },
+                "dept_id",
+                Some("user_id"),
+            )
+            .where_eq("status", Some("0"));
+        assert_eq!(
+            sql_builder.quer_sql(),
+            "select * from sys_user WHERE (dept_id = ANY($1) OR user_id = $2) AND status = $3"
+        );
+        assert_eq!(
+            sql_builder.count_sql(),
+            Some(
+                "SELECT COUNT(*) FROM sys_user WHERE (dept_id = ANY($1) OR user_id = $2) AND status = $3"
+            )
+        );
+
+        let mut sql_builder = SqlBuilder::new(&db, "select * from sys_dept where del_flag = '0'");
+        sql_builder.where_data_scope(
+            &DataScope::Limited {
+                user_id: 2,
+                dept_ids: vec![1, 3],
+            },
+            "dept_id",
+            None,
+        );
+        assert_eq!(
+            sql_builder.quer_sql(),
+            "select * from sys_dept where del_flag = '0' AND (dept_id = ANY($1))"
+        );
+    }
+
+    #[test]
+    fn contains_dept_test() {
+        assert!(DataScope::All.contains_dept(None));
+        let data_scope = DataScope::Limited {
+            user_id: 2,
+            dept_ids: vec![1, 3],
+        };
+        assert!(data_scope.contains_dept(Some(3)));
+        assert!(!data_scope.contains_dept(Some(2)));
+        assert!(!data_scope.contains_dept(None));
+    }
+}
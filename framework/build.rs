@@ -0,0 +1,4 @@
+// 迁移脚本由 `sqlx::migrate!` 在编译时嵌入，脚本变化时重新编译
+fn main() {
+    println!("cargo:rerun-if-changed=../migrations");
+}
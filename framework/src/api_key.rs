@@ -0,0 +1,228 @@
+//! 个人访问令牌（API Key），供 CI、批处理脚本等无法交互登录的客户端使用
+//!
+//! 令牌格式为 `ck_{前缀}_{密钥}`，前缀用于查找记录，数据库中只保存整个令牌的 SHA-256 摘要。
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use common::{AppError, AppResult};
+use moka::future::Cache;
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use tracing::{error, warn};
+
+/// API Key 固定前缀，用于与 JWT 区分
+pub const API_KEY_PREFIX: &str = "ck_";
+/// 通过 API Key 认证时，Depot 中存放 [`ApiKeyAuth`] 的 key
+pub const API_KEY: &str = "api_key";
+/// 除 `Authorization: Bearer` 外，也可通过该请求头传递 API Key
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// API Key 记录（由业务模块从数据库加载）
+#[derive(Debug, Clone)]
+pub struct ApiKeyRecord {
+    pub key_id: i32,
+    pub user_id: i32,
+    pub key_hash: String,
+    /// 允许使用的权限标识
+    pub scopes: HashSet<String>,
+    pub expire_time: Option<OffsetDateTime>,
+}
+
+/// 已通过认证的 API Key
+#[derive(Debug, Clone)]
+pub struct ApiKeyAuth {
+    pub key_id: i32,
+    pub user_id: i32,
+    pub scopes: Arc<HashSet<String>>,
+}
+
+/// 新生成的 API Key，明文令牌只在创建时返回一次
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub key: String,
+    pub prefix: String,
+    pub key_hash: String,
+}
+
+/// API Key 存储
+///
+/// 与 [`crate::permission::PermissionLoader`] 一样，数据表属于业务模块，
+/// 由业务模块实现该 trait 并在启动时通过 [`crate::state::AppState::with`] 注册 [`ApiKeyTool`]。
+#[async_trait]
+pub trait ApiKeyStore: Send + Sync {
+    /// 根据前缀查询未吊销、所属用户状态正常的 API Key
+    async fn find(&self, prefix: &str) -> AppResult<Option<ApiKeyRecord>>;
+    /// 记录最近使用时间
+    async fn touch(&self, key_id: i32) -> AppResult<()>;
+}
+
+/// API Key 工具：生成、校验令牌，缓存查询结果并节流记录使用时间，克隆后共享同一缓存
+#[derive(Clone)]
+pub struct ApiKeyTool {
+    store: Arc<dyn ApiKeyStore>,
+    /// 按前缀缓存的记录，吊销时需调用 `invalidate`
+    cache: Cache<String, Arc<ApiKeyRecord>>,
+    /// 最近已记录使用时间的 key，每分钟最多写一次数据库
+    touched: Cache<i32, ()>,
+}
+
+impl ApiKeyTool {
+    pub fn new(store: impl ApiKeyStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+            cache: Cache::builder()
+                .max_capacity(1000)
+                .time_to_live(Duration::from_secs(60))
+                .build(),
+            touched: Cache::builder()
+                .max_capacity(1000)
+                .time_to_live(Duration::from_secs(60))
+                .build(),
+        }
+    }
+
+    /// 生成新的 API Key（前缀 48 位、密钥 256 位，均取自系统随机数源）
+    pub fn generate() -> AppResult<NewApiKey> {
+        let mut prefix = [0u8; 6];
+        let mut secret = [0u8; 32];
+        getrandom::fill(&mut prefix)
+            .and_then(|_| getrandom::fill(&mut secret))
+            .map_err(|e| AppError::Other(format!("生成 API Key 失败: {e}")))?;
+        let prefix: String = prefix.iter().map(|b| format!("{b:02x}")).collect();
+        let key = format!(
+            "{API_KEY_PREFIX}{prefix}_{}",
+            URL_SAFE_NO_PAD.encode(secret)
+        );
+        let key_hash = Self::hash(&key);
+        Ok(NewApiKey {
+            key,
+            prefix,
+            key_hash,
+        })
+    }
+
+    /// 令牌摘要（令牌本身为高熵随机数，无需慢哈希）
+    pub fn hash(key: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(key.as_bytes()))
+    }
+
+    pub fn is_api_key(token: &str) -> bool {
+        token.starts_with(API_KEY_PREFIX)
+    }
+
+    /// 校验 API Key，通过后返回所属用户及允许的权限
+    pub async fn verify(&self, key: &str) -> AppResult<ApiKeyAuth> {
+        let prefix = key
+            .strip_prefix(API_KEY_PREFIX)
+            .and_then(|rest| rest.split_once('_'))
+            .map(|(prefix, _)| prefix)
+            .ok_or(AppError::TokenInvalid)?;
+        let record = match self.cache.get(prefix).await {
+            Some(record) => record,
+            None => {
+                let record = Arc::new(
+                    self.store
+                        .find(prefix)
+                        .await?
+                        .ok_or(AppError::TokenInvalid)?,
+                );
+                self.cache.insert(prefix.to_string(), record.clone()).await;
+                record
+            }
+        };
+        if Self::hash(key) != record.key_hash {
+            warn!("[API_KEY] Hash mismatch for key prefix: {}", prefix);
+            return Err(AppError::TokenInvalid);
+        }
+        if record
+            .expire_time
+            .is_some_and(|expire| expire <= OffsetDateTime::now_utc())
+        {
+            return Err(AppError::TokenInvalid);
+        }
+        if !self.touched.contains_key(&record.key_id) {
+            self.touched.insert(record.key_id, ()).await;
+            if let Err(e) = self.store.touch(record.key_id).await {
+                error!("[API_KEY] Failed to record last used time: {:?}", e);
+            }
+        }
+        Ok(ApiKeyAuth {
+            key_id: record.key_id,
+            user_id: record.user_id,
+            scopes: Arc::new(record.scopes.clone()),
+        })
+    }
+
+    /// 清除缓存（吊销时调用，使其立即失效）
+    pub async fn invalidate(&self, prefix: &str) {
+        self.cache.invalidate(prefix).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use async_trait::async_trait;
+    use common::AppResult;
+    use time::{Duration, OffsetDateTime};
+
+    use crate::api_key::{ApiKeyRecord, ApiKeyStore, ApiKeyTool};
+
+    struct FakeStore {
+        record: ApiKeyRecord,
+        touches: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ApiKeyStore for FakeStore {
+        async fn find(&self, prefix: &str) -> AppResult<Option<ApiKeyRecord>> {
+            Ok(Some(self.record.clone()).filter(|_| prefix.len() == 12))
+        }
+        async fn touch(&self, _key_id: i32) -> AppResult<()> {
+            self.touches.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_test() -> AppResult<()> {
+        let new_key = ApiKeyTool::generate()?;
+        assert!(ApiKeyTool::is_api_key(&new_key.key));
+        let touches = Arc::new(AtomicUsize::new(0));
+        let record = ApiKeyRecord {
+            key_id: 1,
+            user_id: 2,
+            key_hash: new_key.key_hash.clone(),
+            scopes: ["system:user:list".to_string()].into(),
+            expire_time: None,
+        };
+        let tool = ApiKeyTool::new(FakeStore {
+            record: record.clone(),
+            touches: touches.clone(),
+        });
+        let auth = tool.verify(&new_key.key).await?;
+        assert_eq!(auth.user_id, 2);
+        assert!(auth.scopes.contains("system:user:list"));
+        tool.verify(&new_key.key).await?;
+        // 使用时间节流记录
+        assert_eq!(touches.load(Ordering::SeqCst), 1);
+        assert!(tool.verify(&format!("{}x", new_key.key)).await.is_err());
+        assert!(tool.verify("ck_invalid").await.is_err());
+
+        let expired = ApiKeyTool::new(FakeStore {
+            record: ApiKeyRecord {
+                expire_time: Some(OffsetDateTime::now_utc() - Duration::minutes(1)),
+                ..record
+            },
+            touches,
+        });
+        assert!(expired.verify(&new_key.key).await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+//! 后台审计任务（操作日志、登录日志写库）跟踪
+//!
+//! 停机时等待任务完成，超时未完成的任务先中止，再以 JSON Lines 格式追加到落盘文件，
+//! 下次启动时由业务模块读取并重新写入，写入失败的任务保留在文件中。
+//!
+//! 任务若在数据库写入提交之后、任务返回之前被中止，仍会按未完成落盘，重放时该条日志会重复写入一次。
+//! 审计日志宁重勿漏，不做去重。
+
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    future::Future,
+    io::Write,
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use common::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{sync::Notify, task::JoinHandle};
+use tracing::{info, warn};
+
+/// 未完成的任务
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpilledTask {
+    /// 任务类型，由业务模块定义
+    pub kind: String,
+    pub payload: Value,
+}
+
+/// 运行中的任务
+struct Pending {
+    task: SpilledTask,
+    handle: JoinHandle<()>,
+}
+
+/// 后台任务跟踪工具，克隆后共享同一组任务
+#[derive(Clone, Default)]
+pub struct BackgroundTasks {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, Pending>>,
+    idle: Notify,
+}
+
+/// 任务结束（包括 panic）时移除跟踪记录
+struct Done(Arc<Inner>, u64);
+
+impl Drop for Done {
+    fn drop(&mut self) {
+        let mut pending = self.0.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.remove(&self.1);
+        if pending.is_empty() {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl BackgroundTasks {
+    /// 以 `payload` 启动后台任务，任务超时未完成时落盘 `payload`
+    pub fn spawn<T, F, Fut>(&self, kind: &str, payload: T, task: F)
+    where
+        T: Serialize,
+        F: FnOnce(T) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let value = serde_json::to_value(&payload).unwrap_or(Value::Null);
+        let task = task(payload);
+        let done = Done(self.inner.clone(), id);
+        // 持锁启动任务，保证任务结束移除记录前记录已存在
+        let mut pending = self.inner.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let handle = tokio::spawn(async move {
+            let _done = done;
+            task.await;
+        });
+        pending.insert(
+            id,
+            Pending {
+                task: SpilledTask {
+                    kind: kind.to_string(),
+                    payload: value,
+                },
+                handle,
+            },
+        );
+    }
+
+    /// 未完成的任务数
+    pub fn pending(&self) -> usize {
+        self.inner
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    /// 等待所有任务完成，超时后中止未完成的任务并追加写入 `spill_path`，返回落盘的任务数
+    ///
+    /// 只有确认已中止的任务才会落盘，避免任务稍后完成导致重放时重复写入。
+    pub async fn drain(&self, timeout: Duration, spill_path: &Path) -> AppResult<usize> {
+        let wait = async {
+            loop {
+                let idle = self.inner.idle.notified();
+                if self.pending() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        };
+        if tokio::time::timeout(timeout, wait).await.is_ok() {
+            return Ok(0);
+        }
+
+        let unfinished: Vec<Pending> = self
+            .inner
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+            .map(|(_, pending)| pending)
+            .collect();
+        for pending in &unfinished {
+            pending.handle.abort();
+        }
+        let mut tasks = Vec::with_capacity(unfinished.len());
+        for Pending { task, handle } in unfinished {
+            // 中止前已完成的任务不再落盘
+            if handle.await.is_err() {
+                tasks.push(task);
+            }
+        }
+        if tasks.is_empty() {
+            return Ok(0);
+        }
+        if let Some(dir) = spill_path.parent() {
+            fs::create_dir_all(dir).map_err(spill_error)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(spill_path)
+            .map_err(spill_error)?;
+        for task in &tasks {
+            writeln!(file, "{}", serde_json::to_string(task)?).map_err(spill_error)?;
+        }
+        warn!(
+            "[BACKGROUND] {} unfinished tasks spilled to {}",
+            tasks.len(),
+            spill_path.display()
+        );
+        Ok(tasks.len())
+    }
+
+    /// 读取落盘的任务，重放后调用 [`Self::finish_spilled`] 更新落盘文件
+    pub fn read_spilled(spill_path: &Path) -> AppResult<Vec<SpilledTask>> {
+        if !spill_path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(spill_path).map_err(spill_error)?;
+        let tasks: Vec<SpilledTask> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|e| warn!("[BACKGROUND] Invalid spilled task {}: {}", line, e))
+                    .ok()
+            })
+            .collect();
+        info!(
+            "[BACKGROUND] Loaded {} spilled tasks from {}",
+            tasks.len(),
+            spill_path.display()
+        );
+        Ok(tasks)
+    }
+
+    /// 重放结束：全部成功时删除落盘文件，否则只保留失败的任务等待下次重放
+    pub fn finish_spilled(spill_path: &Path, failed: &[SpilledTask]) -> AppResult<()> {
+        if failed.is_empty() {
+            return match fs::remove_file(spill_path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(spill_error(e)),
+                _ => Ok(()),
+            };
+        }
+        // 先写临时文件再替换，中途退出时原文件不受影响
+        let tmp_path = spill_path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path).map_err(spill_error)?;
+        for task in failed {
+            writeln!(file, "{}", serde_json::to_string(task)?).map_err(spill_error)?;
+        }
+        file.sync_all().map_err(spill_error)?;
+        fs::rename(&tmp_path, spill_path).map_err(spill_error)?;
+        warn!(
+            "[BACKGROUND] {} spilled tasks failed to replay, kept in {}",
+            failed.len(),
+            spill_path.display()
+        );
+        Ok(())
+    }
+}
+
+fn spill_error(e: std::io::Error) -> AppError {
+    AppError::Other(format!("后台任务落盘失败: {e}"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    };
+
+    use serde_json::json;
+
+    use super::BackgroundTasks;
+
+    #[tokio::test]
+    async fn drain_test() {
+        let tasks = BackgroundTasks::default();
+        let spill_path =
+            std::env::temp_dir().join(format!("spill-{}.jsonl", uuid::Uuid::new_v4().simple()));
+
+        tasks.spawn("quick", json!({"n": 1}), |_| async {});
+        let spilled = tasks
+            .drain(Duration::from_secs(1), &spill_path)
+            .await
+            .unwrap();
+        assert_eq!(spilled, 0);
+        assert!(!spill_path.exists());
+
+        let completed = Arc::new(AtomicBool::new(false));
+        let flag = completed.clone();
+        tasks.spawn("slow", json!({"n": 2}), move |_| async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            flag.store(true, Ordering::SeqCst);
+        });
+        let spilled = tasks
+            .drain(Duration::from_millis(50), &spill_path)
+            .await
+            .unwrap();
+        assert_eq!(spilled, 1);
+        assert_eq!(tasks.pending(), 0);
+        // 落盘的任务已中止，不会再完成
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert!(!completed.load(Ordering::SeqCst));
+
+        let spilled = BackgroundTasks::read_spilled(&spill_path).unwrap();
+        assert_eq!(spilled.len(), 1);
+        assert_eq!(spilled[0].kind, "slow");
+        assert_eq!(spilled[0].payload["n"], 2);
+
+        // 重放失败的任务保留，成功后删除文件
+        BackgroundTasks::finish_spilled(&spill_path, &spilled).unwrap();
+        let spilled = BackgroundTasks::read_spilled(&spill_path).unwrap();
+        assert_eq!(spilled.len(), 1);
+        assert_eq!(spilled[0].kind, "slow");
+        BackgroundTasks::finish_spilled(&spill_path, &[]).unwrap();
+        assert!(!spill_path.exists());
+    }
+}
@@ -0,0 +1,230 @@
+//! 客户端真实地址解析
+//!
+//! 只有直连地址属于受信任代理时才读取转发请求头（优先级：`Forwarded` > `X-Forwarded-For` > `X-Real-IP`），
+//! 并从右往左跳过受信任代理，取第一个不受信任的地址，避免客户端伪造请求头。
+
+use std::{net::IpAddr, sync::Arc};
+
+use common::{AppError, AppResult};
+use ipnet::IpNet;
+use salvo::{Request, http::HeaderMap};
+
+use crate::{config::Proxy, state::AppState};
+
+/// 客户端地址解析工具
+#[derive(Clone)]
+pub struct ClientIpTool {
+    trusted: Arc<[IpNet]>,
+}
+
+impl ClientIpTool {
+    pub fn new(config: Proxy) -> AppResult<Self> {
+        let trusted = config
+            .trusted
+            .iter()
+            .map(|item| {
+                item.parse::<IpNet>()
+                    .or_else(|_| item.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| AppError::Other(format!("受信任代理地址 {item} 格式错误")))
+            })
+            .collect::<AppResult<Arc<[_]>>>()?;
+        Ok(Self { trusted })
+    }
+
+    fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.trusted.iter().any(|net| net.contains(ip))
+    }
+
+    /// 解析客户端地址
+    pub fn resolve(&self, req: &Request) -> Option<IpAddr> {
+        let peer = peer_ip(req)?;
+        if !self.is_trusted(&peer) {
+            return Some(peer);
+        }
+        let chain = forwarded_chain(req.headers());
+        if chain.is_empty() {
+            return Some(real_ip(req.headers()).unwrap_or(peer));
+        }
+        let mut hop = peer;
+        for node in chain.into_iter().rev() {
+            // 无法识别的节点（如 unknown、混淆标识）之前的内容不可信
+            let Some(ip) = node else {
+                break;
+            };
+            hop = ip;
+            if !self.is_trusted(&ip) {
+                break;
+            }
+        }
+        Some(hop)
+    }
+}
+
+/// 获取客户端地址，应用状态中没有解析工具时使用直连地址
+pub fn client_ip(req: &Request) -> String {
+    match AppState::from_request(req).and_then(|state| state.ext::<ClientIpTool>()) {
+        Ok(tool) => tool.resolve(req),
+        Err(_) => peer_ip(req),
+    }
+    .map(|ip| ip.to_string())
+    .unwrap_or_default()
+}
+
+/// 直连地址（IPv4 映射的 IPv6 地址统一转换为 IPv4）
+fn peer_ip(req: &Request) -> Option<IpAddr> {
+    req.remote_addr()
+        .clone()
+        .into_std()
+        .map(|addr| addr.ip().to_canonical())
+}
+
+/// 转发链路上的地址，从客户端到最后一个代理依次排列
+fn forwarded_chain(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let forwarded: Vec<Option<IpAddr>> = headers
+        .get_all("Forwarded")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| parse_node(value))
+            })
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+    headers
+        .get_all("X-Forwarded-For")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter(|node| !node.trim().is_empty())
+        .map(parse_node)
+        .collect()
+}
+
+fn real_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get("X-Real-IP")
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_node)
+}
+
+/// 解析 `1.2.3.4`、`1.2.3.4:80`、`2001:db8::1`、`"[2001:db8::1]:80"` 等形式
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    let ip = if let Some(rest) = node.strip_prefix('[') {
+        rest.split_once(']')?.0.parse().ok()?
+    } else if let Ok(ip) = node.parse::<IpAddr>() {
+        ip
+    } else {
+        let (host, _port) = node.rsplit_once(':')?;
+        IpAddr::V4(host.parse().ok()?)
+    };
+    Some(ip.to_canonical())
+}
+
+#[cfg(test)]
+mod test {
+    use salvo::Request;
+
+    use crate::client_ip::{ClientIpTool, parse_node};
+    use crate::config::Proxy;
+
+    fn request(peer: &str, headers: &[(&'static str, &str)]) -> Request {
+        let mut req = Request::default();
+        *req.remote_addr_mut() = peer.parse::<std::net::SocketAddr>().unwrap().into();
+        for (name, value) in headers {
+            req.headers_mut().append(*name, value.parse().unwrap());
+        }
+        req
+    }
+
+    fn resolve(tool: &ClientIpTool, peer: &str, headers: &[(&'static str, &str)]) -> String {
+        tool.resolve(&request(peer, headers)).unwrap().to_string()
+    }
+
+    #[test]
+    fn parse_node_test() {
+        assert_eq!(parse_node(" 1.2.3.4 ").unwrap().to_string(), "1.2.3.4");
+        assert_eq!(parse_node("1.2.3.4:8080").unwrap().to_string(), "1.2.3.4");
+        assert_eq!(
+            parse_node("2001:db8::1").unwrap().to_string(),
+            "2001:db8::1"
+        );
+        assert_eq!(
+            parse_node("\"[2001:db8::1]:443\"").unwrap().to_string(),
+            "2001:db8::1"
+        );
+        assert_eq!(parse_node("::ffff:1.2.3.4").unwrap().to_string(), "1.2.3.4");
+        assert!(parse_node("unknown").is_none());
+        assert!(parse_node("_hidden").is_none());
+    }
+
+    #[test]
+    fn resolve_test() {
+        let tool = ClientIpTool::new(Proxy {
+            trusted: vec!["10.0.0.0/8".into(), "::1".into()],
+        })
+        .unwrap();
+        // 不受信任的直连地址忽略转发请求头
+        assert_eq!(
+            resolve(&tool, "1.1.1.1:5000", &[("X-Forwarded-For", "9.9.9.9")]),
+            "1.1.1.1"
+        );
+        assert_eq!(resolve(&tool, "[2001:db8::2]:5000", &[]), "2001:db8::2");
+        // 从右往左跳过受信任代理，客户端伪造的最左侧地址不会被采用
+        assert_eq!(
+            resolve(
+                &tool,
+                "10.0.0.1:5000",
+                &[("X-Forwarded-For", "9.9.9.9, 2.2.2.2, 10.0.0.2")]
+            ),
+            "2.2.2.2"
+        );
+        assert_eq!(
+            resolve(
+                &tool,
+                "10.0.0.1:5000",
+                &[
+                    ("X-Forwarded-For", "9.9.9.9"),
+                    ("X-Forwarded-For", "10.0.0.3")
+                ]
+            ),
+            "9.9.9.9"
+        );
+        // Forwarded 优先于 X-Forwarded-For
+        assert_eq!(
+            resolve(
+                &tool,
+                "[::1]:5000",
+                &[
+                    (
+                        "Forwarded",
+                        "for=\"[2001:db8::9]:1234\";proto=https, for=10.0.0.5"
+                    ),
+                    ("X-Forwarded-For", "3.3.3.3")
+                ]
+            ),
+            "2001:db8::9"
+        );
+        assert_eq!(
+            resolve(&tool, "10.0.0.1:5000", &[("X-Real-IP", "4.4.4.4")]),
+            "4.4.4.4"
+        );
+        // 链路中出现无法识别的节点时停在最后一个可识别的地址
+        assert_eq!(
+            resolve(
+                &tool,
+                "10.0.0.1:5000",
+                &[("X-Forwarded-For", "9.9.9.9, unknown, 10.0.0.2")]
+            ),
+            "10.0.0.2"
+        );
+        assert_eq!(resolve(&tool, "10.0.0.1:5000", &[]), "10.0.0.1");
+    }
+}
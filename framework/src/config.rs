@@ -1,8 +1,10 @@
 use std::{collections::HashMap, env};
 
-use common::AppError;
+use common::{AppError, utils::time::opt_offset};
 use config::{Config, Environment, File};
+use jsonwebtoken::Algorithm;
 use serde::Deserialize;
+use time::OffsetDateTime;
 use tracing::info;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -20,6 +22,9 @@ pub struct Database {
     port: u16,
     username: String,
     password: String,
+    /// 启动时自动执行未执行的数据库迁移，关闭时数据库结构落后则拒绝启动
+    #[serde(default)]
+    pub auto_migrate: bool,
 }
 impl Database {
     pub fn get_url(&self) -> String {
@@ -42,6 +47,109 @@ pub struct JWT {
     pub issuer: String,
     pub acc_expiration_hour: u8,
     pub ref_expiration_hour: u8,
+    /// 非对称签名密钥，为空时使用 `secret` 进行 HS256 签名
+    #[serde(default)]
+    pub keys: Vec<JwtKey>,
+}
+
+/// JWT 非对称签名密钥（PEM 文件）
+#[derive(Debug, Deserialize, Clone)]
+pub struct JwtKey {
+    /// 密钥编号，写入令牌头部的 `kid`
+    pub kid: String,
+    /// 签名算法：RS256/RS384/RS512/PS256/PS384/PS512/ES256/ES384/EdDSA
+    pub algorithm: Algorithm,
+    /// 私钥路径，未配置时该密钥仅用于验签（如已停止签发的旧密钥）
+    pub private_key: Option<String>,
+    /// 公钥路径
+    pub public_key: String,
+    /// 开始签发时间，未到该时间前仅发布到 JWKS
+    #[serde(default, with = "opt_offset")]
+    pub active_from: Option<OffsetDateTime>,
+    /// 下线时间，到期后不再用于验签，也不再发布到 JWKS
+    #[serde(default, with = "opt_offset")]
+    pub retire_at: Option<OffsetDateTime>,
+}
+
+/// 登录防暴力破解配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Login {
+    /// 统计窗口内同一用户名允许的最大失败次数
+    pub max_retry_user: u32,
+    /// 统计窗口内同一IP允许的最大失败次数
+    pub max_retry_ip: u32,
+    /// 失败次数统计窗口，单位：分钟
+    pub window_minutes: u64,
+    /// 锁定时长，单位：分钟
+    pub lock_minutes: u64,
+    /// 失败计数及锁定记录各自最多保留的条目数
+    pub max_capacity: u64,
+}
+impl Default for Login {
+    fn default() -> Self {
+        Self {
+            max_retry_user: 5,
+            max_retry_ip: 20,
+            window_minutes: 10,
+            lock_minutes: 10,
+            max_capacity: 10_000,
+        }
+    }
+}
+
+/// 在线会话配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Session {
+    /// 最多保留的在线会话数，超出后淘汰最少使用的会话（被淘汰的会话需重新登录）
+    pub max_capacity: u64,
+}
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            max_capacity: 100_000,
+        }
+    }
+}
+
+/// 密码策略配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Password {
+    /// 最小长度
+    pub min_length: usize,
+    /// 最大长度
+    pub max_length: usize,
+    /// 必须包含大写字母
+    pub require_uppercase: bool,
+    /// 必须包含小写字母
+    pub require_lowercase: bool,
+    /// 必须包含数字
+    pub require_digit: bool,
+    /// 必须包含特殊字符
+    pub require_special: bool,
+    /// 不允许与最近 N 次使用过的密码相同，0 表示不限制
+    pub history: usize,
+    /// 密码有效期，单位：天，0 表示永不过期
+    pub expire_days: u64,
+    /// 追加的弱密码列表文件（每行一个）
+    pub banned_file: Option<String>,
+}
+impl Default for Password {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 64,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: false,
+            history: 5,
+            expire_days: 90,
+            banned_file: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -50,6 +158,328 @@ pub struct Setting {
     pub database: Database,
     pub jwt: JWT,
     pub upload: Upload,
+    #[serde(default)]
+    pub login: Login,
+    #[serde(default)]
+    pub session: Session,
+    #[serde(default)]
+    pub password: Password,
+    #[serde(default)]
+    pub register: Register,
+    /// OIDC 单点登录提供方
+    #[serde(default)]
+    pub oidc: Vec<OidcProvider>,
+    #[serde(default)]
+    pub ip_location: IpLocation,
+    #[serde(default)]
+    pub proxy: Proxy,
+    #[serde(default)]
+    pub cors: Cors,
+    #[serde(default)]
+    pub security_headers: SecurityHeaders,
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+    #[serde(default)]
+    pub log: Log,
+    #[serde(default)]
+    pub metrics: Metrics,
+    #[serde(default)]
+    pub shutdown: Shutdown,
+}
+
+/// 停机配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Shutdown {
+    /// 停机时等待进行中的请求、后台日志写入完成的时间（各自计算），单位：秒
+    pub drain_timeout_secs: u64,
+    /// 超时未写入数据库的操作日志、登录日志落盘文件，下次启动时重新写入
+    pub spill_path: String,
+}
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            drain_timeout_secs: 30,
+            spill_path: "logs/audit-spill.jsonl".to_string(),
+        }
+    }
+}
+
+/// Prometheus 指标配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Metrics {
+    /// 是否开启 `/metrics` 端点
+    pub enabled: bool,
+    /// 采集令牌，配置后需携带 `Authorization: Bearer {token}`
+    pub token: String,
+}
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            token: String::new(),
+        }
+    }
+}
+
+/// 日志配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Log {
+    /// 日志级别或过滤指令，如 `info,sqlx=warn`；设置了 `RUST_LOG` 环境变量时以环境变量为准
+    pub level: String,
+    pub format: LogFormat,
+    /// 是否输出到控制台
+    pub console: bool,
+    /// 日志文件目录，为空时不写文件
+    pub dir: String,
+    pub file_name: String,
+    /// 错误日志文件名（只记录 ERROR 级别），为空时不单独记录
+    pub error_file_name: String,
+    pub rotation: LogRotation,
+    /// 按大小滚动时单个文件的上限，单位：MB
+    pub max_size_mb: u64,
+    /// 保留的历史日志文件数，0 表示不清理
+    pub max_files: usize,
+}
+impl Default for Log {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+            console: true,
+            dir: String::new(),
+            file_name: "app.log".to_string(),
+            error_file_name: "error.log".to_string(),
+            rotation: LogRotation::Daily,
+            max_size_mb: 100,
+            max_files: 30,
+        }
+    }
+}
+
+/// 日志格式
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// 单行文本
+    Text,
+    /// 多行文本，便于开发时阅读
+    Pretty,
+    /// 每行一个 JSON 对象，便于日志平台采集
+    Json,
+}
+
+/// 日志文件滚动方式
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    /// 按天（UTC）
+    Daily,
+    /// 按文件大小
+    Size,
+    Never,
+}
+
+/// 限流配置（令牌桶）
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RateLimit {
+    pub enabled: bool,
+    /// 未匹配任何路由规则时使用的规则
+    pub default: RateLimitRule,
+    /// 路由规则，按顺序匹配第一个路径前缀相符的规则
+    pub routes: Vec<RateLimitRule>,
+}
+impl Default for RateLimit {
+    fn default() -> Self {
+        let rule = |path: &str, capacity| RateLimitRule {
+            path: path.to_string(),
+            capacity,
+            ..Default::default()
+        };
+        Self {
+            enabled: true,
+            default: rule("", 0),
+            routes: vec![rule("/sys/catpcha", 20), rule("/sys/login", 10)],
+        }
+    }
+}
+
+/// 限流规则：桶容量为 `capacity`，每 `period_secs` 秒补满一桶
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RateLimitRule {
+    /// 路由前缀，如 `/sys/login`
+    pub path: String,
+    pub key: RateLimitKey,
+    /// 桶容量（允许的突发请求数），0 表示不限制
+    pub capacity: u32,
+    pub period_secs: u64,
+}
+impl Default for RateLimitRule {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            key: RateLimitKey::Ip,
+            capacity: 0,
+            period_secs: 60,
+        }
+    }
+}
+
+/// 限流维度
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitKey {
+    /// 按客户端IP
+    Ip,
+    /// 按登录用户，未登录时按客户端IP
+    User,
+    /// 按路由，所有客户端共用一个桶
+    Route,
+}
+
+/// 跨域配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Cors {
+    /// 是否开启跨域支持
+    pub enabled: bool,
+    /// 允许的来源，`*` 表示任意来源
+    pub allow_origins: Vec<String>,
+    pub allow_methods: Vec<String>,
+    /// 允许的请求头，`*` 表示预检请求中声明的任意请求头
+    pub allow_headers: Vec<String>,
+    /// 允许前端读取的响应头
+    pub expose_headers: Vec<String>,
+    /// 是否允许携带 Cookie 等凭据（开启时不会返回 `*`，而是回显请求来源）
+    pub allow_credentials: bool,
+    /// 预检结果缓存时间，单位：秒
+    pub max_age: u64,
+}
+impl Default for Cors {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allow_origins: Vec::new(),
+            allow_methods: ["GET", "POST", "PUT", "DELETE", "OPTIONS"]
+                .map(String::from)
+                .to_vec(),
+            allow_headers: ["Authorization", "Content-Type", "X-API-Key"]
+                .map(String::from)
+                .to_vec(),
+            expose_headers: vec!["X-Request-Id".to_string()],
+            allow_credentials: false,
+            max_age: 3600,
+        }
+    }
+}
+
+/// 安全响应头配置，配置为空字符串时不输出对应响应头
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SecurityHeaders {
+    pub enabled: bool,
+    /// Strict-Transport-Security
+    pub hsts: String,
+    /// X-Frame-Options
+    pub frame_options: String,
+    /// X-Content-Type-Options: nosniff
+    pub content_type_options: bool,
+    /// Referrer-Policy
+    pub referrer_policy: String,
+    /// Content-Security-Policy，只对 `csp_paths` 下的页面输出
+    pub content_security_policy: String,
+    pub csp_paths: Vec<String>,
+}
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hsts: "max-age=31536000; includeSubDomains".to_string(),
+            frame_options: "DENY".to_string(),
+            content_type_options: true,
+            referrer_policy: "strict-origin-when-cross-origin".to_string(),
+            content_security_policy: "default-src 'self'; script-src 'self' 'unsafe-inline'; \
+                style-src 'self' 'unsafe-inline'; img-src 'self' data:; frame-ancestors 'none'"
+                .to_string(),
+            csp_paths: vec!["/admin".to_string(), "/swagger-ui".to_string()],
+        }
+    }
+}
+
+/// 反向代理配置
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Proxy {
+    /// 受信任的代理地址（IP 或 CIDR），只有来自这些地址的请求才会读取
+    /// `Forwarded`/`X-Forwarded-For`/`X-Real-IP` 请求头
+    pub trusted: Vec<String>,
+}
+
+/// IP 归属地查询配置（ip2region xdb 离线库）
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct IpLocation {
+    /// xdb 数据文件路径，为空时只识别内网地址
+    pub xdb_path: Option<String>,
+    /// 查询结果缓存条数
+    pub cache_size: u64,
+}
+impl Default for IpLocation {
+    fn default() -> Self {
+        Self {
+            xdb_path: None,
+            cache_size: 10000,
+        }
+    }
+}
+
+/// 用户自助注册配置
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Register {
+    /// 是否开启注册
+    pub enabled: bool,
+    /// 注册用户默认分配的角色标识
+    pub default_roles: Vec<String>,
+    /// 是否需要管理员审核，审核通过前账号为停用状态
+    pub require_approval: bool,
+}
+
+/// OIDC 单点登录提供方
+#[derive(Debug, Deserialize, Clone)]
+pub struct OidcProvider {
+    /// 提供方名称，对应路由 `/sys/sso/{name}/...`
+    pub name: String,
+    /// Issuer 地址，从 `{issuer}/.well-known/openid-configuration` 获取发现文档
+    pub issuer: String,
+    pub client_id: String,
+    /// 客户端密钥，公共客户端（仅使用 PKCE）可不配置
+    pub client_secret: Option<String>,
+    /// 回调地址，需与在提供方注册的一致
+    pub redirect_uri: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+    /// 允许的 ID Token 签名算法，默认只接受 RS256；HS 系列需显式配置
+    #[serde(default = "default_oidc_algorithms")]
+    pub algorithms: Vec<Algorithm>,
+    /// 外部账号未绑定时是否自动创建用户
+    #[serde(default)]
+    pub auto_provision: bool,
+    /// 自动创建的用户默认分配的角色标识
+    #[serde(default)]
+    pub default_roles: Vec<String>,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec!["openid".into(), "profile".into(), "email".into()]
+}
+
+fn default_oidc_algorithms() -> Vec<Algorithm> {
+    vec![Algorithm::RS256]
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -62,6 +492,11 @@ pub struct Upload {
 impl Setting {
     pub fn init() -> Result<Self, AppError> {
         let run_mode = env::var("RUN_MODE").unwrap_or_else(|_| "dev".into());
+        Self::load(&run_mode)
+    }
+
+    /// 加载 `config/{run_mode}` 配置文件，环境变量 `APP_*` 可覆盖
+    pub fn load(run_mode: &str) -> Result<Self, AppError> {
         let config_file_path = format!("config/{}", run_mode);
         info!("loading config file: {}", config_file_path);
         let setting = Config::builder()
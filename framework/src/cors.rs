@@ -0,0 +1,267 @@
+//! 跨域（CORS）与安全响应头
+
+use std::sync::Arc;
+
+use salvo::{
+    Request, Response,
+    http::{
+        HeaderValue, Method, StatusCode,
+        header::{
+            ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+            ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+            ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_HEADERS,
+            ACCESS_CONTROL_REQUEST_METHOD, CONTENT_SECURITY_POLICY, ORIGIN, REFERRER_POLICY,
+            STRICT_TRANSPORT_SECURITY, VARY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+        },
+    },
+};
+
+use crate::config::{Cors, SecurityHeaders};
+
+/// 跨域策略
+#[derive(Clone)]
+pub struct CorsPolicy {
+    config: Arc<Cors>,
+}
+
+impl CorsPolicy {
+    pub fn new(config: Cors) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn origin_allowed(&self, origin: &str) -> bool {
+        self.config
+            .allow_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+
+    /// 为响应添加跨域响应头；预检请求直接应答，返回 `true` 表示无需继续处理
+    pub fn apply(&self, req: &Request, res: &mut Response) -> bool {
+        let Some(origin) = req.headers().get(ORIGIN).cloned() else {
+            return false;
+        };
+        let preflight = req.method() == Method::OPTIONS
+            && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD);
+        if !origin.to_str().is_ok_and(|o| self.origin_allowed(o)) {
+            if preflight {
+                res.status_code(StatusCode::FORBIDDEN);
+            }
+            return preflight;
+        }
+
+        let config = &self.config;
+        let any_origin = config.allow_origins.iter().any(|o| o == "*");
+        if any_origin && !config.allow_credentials {
+            res.add_header(ACCESS_CONTROL_ALLOW_ORIGIN, "*", true).ok();
+        } else {
+            res.add_header(ACCESS_CONTROL_ALLOW_ORIGIN, origin, true)
+                .ok();
+            res.add_header(VARY, "Origin", false).ok();
+        }
+        if config.allow_credentials {
+            res.add_header(ACCESS_CONTROL_ALLOW_CREDENTIALS, "true", true)
+                .ok();
+        }
+        if !preflight {
+            if !config.expose_headers.is_empty() {
+                res.add_header(
+                    ACCESS_CONTROL_EXPOSE_HEADERS,
+                    config.expose_headers.join(", "),
+                    true,
+                )
+                .ok();
+            }
+            return false;
+        }
+
+        res.add_header(
+            ACCESS_CONTROL_ALLOW_METHODS,
+            config.allow_methods.join(", "),
+            true,
+        )
+        .ok();
+        let allow_headers = if config.allow_headers.iter().any(|h| h == "*") {
+            req.headers()
+                .get(ACCESS_CONTROL_REQUEST_HEADERS)
+                .cloned()
+                .unwrap_or(HeaderValue::from_static(""))
+        } else {
+            HeaderValue::from_str(&config.allow_headers.join(", "))
+                .unwrap_or(HeaderValue::from_static(""))
+        };
+        res.add_header(ACCESS_CONTROL_ALLOW_HEADERS, allow_headers, true)
+            .ok();
+        res.add_header(ACCESS_CONTROL_MAX_AGE, config.max_age, true)
+            .ok();
+        res.status_code(StatusCode::NO_CONTENT);
+        true
+    }
+}
+
+/// 安全响应头
+#[derive(Clone)]
+pub struct SecurityHeadersTool {
+    config: Arc<SecurityHeaders>,
+}
+
+impl SecurityHeadersTool {
+    pub fn new(config: SecurityHeaders) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// 添加安全响应头，CSP 只对配置的页面路径输出
+    pub fn apply(&self, path: &str, res: &mut Response) {
+        let config = &self.config;
+        let headers = [
+            (STRICT_TRANSPORT_SECURITY, config.hsts.as_str()),
+            (X_FRAME_OPTIONS, config.frame_options.as_str()),
+            (REFERRER_POLICY, config.referrer_policy.as_str()),
+            (
+                X_CONTENT_TYPE_OPTIONS,
+                if config.content_type_options {
+                    "nosniff"
+                } else {
+                    ""
+                },
+            ),
+        ];
+        for (name, value) in headers {
+            if !value.is_empty() {
+                res.add_header(name, value, true).ok();
+            }
+        }
+        let csp_page = config.csp_paths.iter().any(|prefix| {
+            path.strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        });
+        if csp_page && !config.content_security_policy.is_empty() {
+            res.add_header(
+                CONTENT_SECURITY_POLICY,
+                config.content_security_policy.as_str(),
+                true,
+            )
+            .ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use salvo::{
+        Request, Response,
+        http::{Method, StatusCode},
+    };
+
+    use crate::config::{Cors, SecurityHeaders};
+    use crate::cors::{CorsPolicy, SecurityHeadersTool};
+
+    fn request(method: Method, headers: &[(&'static str, &str)]) -> Request {
+        let mut req = Request::default();
+        *req.method_mut() = method;
+        for (name, value) in headers {
+            req.headers_mut().insert(*name, value.parse().unwrap());
+        }
+        req
+    }
+
+    fn header<'a>(res: &'a Response, name: &str) -> Option<&'a str> {
+        res.headers().get(name).and_then(|v| v.to_str().ok())
+    }
+
+    #[test]
+    fn cors_test() {
+        let cors = CorsPolicy::new(Cors {
+            enabled: true,
+            allow_origins: vec!["http://localhost:5173".into()],
+            allow_credentials: true,
+            expose_headers: vec!["X-Request-Id".into()],
+            ..Default::default()
+        });
+
+        // 同源请求不处理
+        let mut res = Response::new();
+        assert!(!cors.apply(&request(Method::GET, &[]), &mut res));
+        assert!(header(&res, "access-control-allow-origin").is_none());
+
+        // 预检请求
+        let mut res = Response::new();
+        let req = request(
+            Method::OPTIONS,
+            &[
+                ("origin", "http://localhost:5173"),
+                ("access-control-request-method", "POST"),
+            ],
+        );
+        assert!(cors.apply(&req, &mut res));
+        assert_eq!(res.status_code, Some(StatusCode::NO_CONTENT));
+        assert_eq!(
+            header(&res, "access-control-allow-origin"),
+            Some("http://localhost:5173")
+        );
+        assert_eq!(
+            header(&res, "access-control-allow-credentials"),
+            Some("true")
+        );
+        assert!(
+            header(&res, "access-control-allow-headers")
+                .unwrap()
+                .contains("Authorization")
+        );
+        assert_eq!(header(&res, "access-control-max-age"), Some("3600"));
+
+        // 普通跨域请求
+        let mut res = Response::new();
+        let req = request(Method::POST, &[("origin", "http://localhost:5173")]);
+        assert!(!cors.apply(&req, &mut res));
+        assert_eq!(
+            header(&res, "access-control-expose-headers"),
+            Some("X-Request-Id")
+        );
+        assert_eq!(header(&res, "vary"), Some("Origin"));
+
+        // 不允许的来源
+        let mut res = Response::new();
+        let req = request(
+            Method::OPTIONS,
+            &[
+                ("origin", "http://evil.example"),
+                ("access-control-request-method", "POST"),
+            ],
+        );
+        assert!(cors.apply(&req, &mut res));
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+        assert!(header(&res, "access-control-allow-origin").is_none());
+    }
+
+    #[test]
+    fn security_headers_test() {
+        let tool = SecurityHeadersTool::new(SecurityHeaders::default());
+        let mut res = Response::new();
+        tool.apply("/sys/login", &mut res);
+        assert_eq!(header(&res, "x-content-type-options"), Some("nosniff"));
+        assert_eq!(header(&res, "x-frame-options"), Some("DENY"));
+        assert!(header(&res, "strict-transport-security").is_some());
+        assert!(header(&res, "content-security-policy").is_none());
+
+        let mut res = Response::new();
+        tool.apply("/swagger-ui/index.html", &mut res);
+        assert!(header(&res, "content-security-policy").is_some());
+
+        let mut res = Response::new();
+        tool.apply("/administrator", &mut res);
+        assert!(header(&res, "content-security-policy").is_none());
+    }
+}
@@ -1,29 +1,26 @@
 use std::time::Duration;
 
-use common::{AppError, AppResult};
+use common::AppResult;
 use sqlx::{Database, Pool, Postgres, pool::PoolOptions};
-use tokio::sync::OnceCell;
 use tracing::info;
 
-static DBPOOLONCELOCK: OnceCell<Pool<Postgres>> = OnceCell::const_new();
+use crate::migrate;
 
 pub struct DBPool;
 impl DBPool {
-    pub async fn inint(url: &str) -> AppResult<&Pool<Postgres>> {
-        let pool = DBPOOLONCELOCK
-            .get_or_init(|| async {
-                create_db_pool::<Postgres>(url)
-                    .await
-                    .expect("数据库初始化失败")
-            })
-            .await;
+    /// 创建连接池，`auto_migrate` 为 true 时执行未执行的迁移，否则数据库结构落后于代码时拒绝启动
+    pub async fn inint(url: &str, auto_migrate: bool) -> AppResult<Pool<Postgres>> {
+        let pool = create_db_pool::<Postgres>(url).await?;
+        if auto_migrate {
+            migrate::run(&pool).await?;
+        } else {
+            migrate::check(&pool).await?;
+        }
         Ok(pool)
     }
-    pub async fn get() -> AppResult<&'static Pool<Postgres>> {
-        let pool = DBPOOLONCELOCK
-            .get()
-            .ok_or(AppError::Other("获取数据库链接失败".to_string()))?;
-        Ok(pool)
+    /// 创建独立的连接池，不检查迁移，供迁移命令使用
+    pub async fn connect(url: &str) -> AppResult<Pool<Postgres>> {
+        Ok(create_db_pool::<Postgres>(url).await?)
     }
 }
 
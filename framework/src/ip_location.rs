@@ -0,0 +1,205 @@
+//! IP 归属地查询，使用 ip2region xdb 格式的离线数据库
+//!
+//! xdb 文件结构：256 字节头部，256×256 个 8 字节的向量索引（按 IP 前两段定位），
+//! 之后是 14 字节一条的段索引（起始IP、结束IP、数据长度、数据偏移）和地区数据。
+
+use std::{fs, net::IpAddr, sync::Arc};
+
+use common::{AppError, AppResult};
+use moka::future::Cache;
+use tracing::info;
+
+use crate::config::IpLocation;
+
+/// 内网地址的归属地
+pub const INTERNAL_IP: &str = "内网IP";
+
+const HEADER_LENGTH: usize = 256;
+const VECTOR_INDEX_COLS: usize = 256;
+const VECTOR_INDEX_SIZE: usize = 8;
+const SEGMENT_INDEX_SIZE: usize = 14;
+
+/// xdb 数据库（整个文件加载到内存）
+struct XdbSearcher {
+    content: Vec<u8>,
+}
+
+impl XdbSearcher {
+    fn new(content: Vec<u8>) -> AppResult<Self> {
+        if content.len() < HEADER_LENGTH + VECTOR_INDEX_COLS * VECTOR_INDEX_COLS * VECTOR_INDEX_SIZE
+        {
+            return Err(AppError::Other("IP 归属地数据文件格式错误".to_string()));
+        }
+        Ok(Self { content })
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        self.content
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        self.content
+            .get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    /// 查询 IPv4 地址对应的地区，格式为 `国家|区域|省份|城市|运营商`
+    fn search(&self, ip: u32) -> Option<&str> {
+        let [b0, b1, ..] = ip.to_be_bytes();
+        let index =
+            HEADER_LENGTH + (b0 as usize * VECTOR_INDEX_COLS + b1 as usize) * VECTOR_INDEX_SIZE;
+        // 该向量块内第一条和最后一条段索引的偏移
+        let start = self.u32_at(index)? as usize;
+        let end = self.u32_at(index + 4)? as usize;
+        if end < start {
+            return None;
+        }
+
+        let (mut low, mut high) = (0usize, (end - start) / SEGMENT_INDEX_SIZE);
+        while low <= high {
+            let mid = (low + high) / 2;
+            let offset = start + mid * SEGMENT_INDEX_SIZE;
+            let start_ip = self.u32_at(offset)?;
+            let end_ip = self.u32_at(offset + 4)?;
+            if ip < start_ip {
+                high = mid.checked_sub(1)?;
+            } else if ip > end_ip {
+                low = mid + 1;
+            } else {
+                let len = self.u16_at(offset + 8)? as usize;
+                let ptr = self.u32_at(offset + 10)? as usize;
+                return std::str::from_utf8(self.content.get(ptr..ptr + len)?).ok();
+            }
+        }
+        None
+    }
+}
+
+/// IP 归属地查询工具：内网地址直接识别，其余地址查询离线库并缓存结果
+#[derive(Clone)]
+pub struct IpLocationTool {
+    searcher: Option<Arc<XdbSearcher>>,
+    cache: Cache<IpAddr, Option<String>>,
+}
+
+impl IpLocationTool {
+    pub fn new(config: IpLocation) -> AppResult<Self> {
+        let searcher = match config.xdb_path.as_deref().filter(|p| !p.is_empty()) {
+            Some(path) => {
+                let content = fs::read(path)
+                    .map_err(|e| AppError::Other(format!("IP 归属地数据 {path} 读取失败: {e}")))?;
+                info!(
+                    "[IP_LOCATION] Loaded xdb from {} ({} bytes)",
+                    path,
+                    content.len()
+                );
+                Some(Arc::new(XdbSearcher::new(content)?))
+            }
+            None => None,
+        };
+        Ok(Self {
+            searcher,
+            cache: Cache::new(config.cache_size),
+        })
+    }
+
+    /// 查询 IP 归属地，无法识别时返回 `None`
+    pub async fn location(&self, ip: &str) -> Option<String> {
+        let ip: IpAddr = ip.trim().parse().ok()?;
+        if is_internal(&ip) {
+            return Some(INTERNAL_IP.to_string());
+        }
+        let searcher = self.searcher.as_ref()?;
+        self.cache
+            .get_with(ip, async {
+                match ip {
+                    IpAddr::V4(v4) => searcher.search(u32::from(v4)).and_then(format_region),
+                    IpAddr::V6(_) => None,
+                }
+            })
+            .await
+    }
+}
+
+/// 内网、回环、链路本地等非公网地址
+fn is_internal(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_private() || v4.is_loopback() || v4.is_link_local() || v4.is_unspecified()
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_internal(&IpAddr::V4(v4));
+            }
+            let segment = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (segment & 0xfe00) == 0xfc00
+                || (segment & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// `中国|0|广东省|深圳市|电信` 格式化为 `中国 广东省 深圳市`
+fn format_region(region: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in region.split('|').take(4) {
+        if !part.is_empty() && part != "0" && !parts.contains(&part) {
+            parts.push(part);
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{net::Ipv4Addr, sync::Arc};
+
+    use super::{HEADER_LENGTH, INTERNAL_IP, IpLocationTool, XdbSearcher};
+    use crate::config::IpLocation;
+
+    /// 构造只包含一个网段 1.2.3.0 - 1.2.3.255 的 xdb
+    fn build_xdb() -> Vec<u8> {
+        let region = "中国|0|广东省|深圳市|电信".as_bytes();
+        let mut content = vec![0u8; HEADER_LENGTH + 256 * 256 * 8];
+        let data_ptr = content.len() as u32;
+        content.extend_from_slice(region);
+        let segment_ptr = content.len() as u32;
+        content.extend_from_slice(&u32::from(Ipv4Addr::new(1, 2, 3, 0)).to_le_bytes());
+        content.extend_from_slice(&u32::from(Ipv4Addr::new(1, 2, 3, 255)).to_le_bytes());
+        content.extend_from_slice(&(region.len() as u16).to_le_bytes());
+        content.extend_from_slice(&data_ptr.to_le_bytes());
+        // 向量索引：1.2.x.x 的首条和末条段索引都是这一条
+        let index = HEADER_LENGTH + (256 + 2) * 8;
+        content[index..index + 4].copy_from_slice(&segment_ptr.to_le_bytes());
+        content[index + 4..index + 8].copy_from_slice(&segment_ptr.to_le_bytes());
+        content
+    }
+
+    #[tokio::test]
+    async fn location_test() {
+        let tool = IpLocationTool {
+            searcher: Some(Arc::new(XdbSearcher::new(build_xdb()).unwrap())),
+            ..IpLocationTool::new(IpLocation::default()).unwrap()
+        };
+        assert_eq!(
+            tool.location("1.2.3.4").await.as_deref(),
+            Some("中国 广东省 深圳市")
+        );
+        assert_eq!(tool.location("1.2.4.4").await, None);
+        assert_eq!(tool.location("8.8.8.8").await, None);
+        assert_eq!(
+            tool.location("127.0.0.1").await.as_deref(),
+            Some(INTERNAL_IP)
+        );
+        assert_eq!(
+            tool.location("192.168.1.10").await.as_deref(),
+            Some(INTERNAL_IP)
+        );
+        assert_eq!(tool.location("::1").await.as_deref(), Some(INTERNAL_IP));
+        assert_eq!(tool.location("fd00::1").await.as_deref(), Some(INTERNAL_IP));
+        assert_eq!(tool.location("unknown").await, None);
+    }
+}
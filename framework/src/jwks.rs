@@ -0,0 +1,136 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use common::{AppError, AppResult};
+use jsonwebtoken::{
+    Algorithm,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters, Jwk,
+        KeyAlgorithm, OctetKeyPairParameters, PublicKeyUse, RSAKeyParameters,
+    },
+};
+use salvo::{Response, Writer, handler, writing::Json};
+use simple_asn1::{ASN1Block, from_der};
+use tracing::info;
+
+use crate::state::Jwt;
+
+/// 公开的 JWKS，供其他服务验证本系统签发的令牌
+#[handler]
+pub async fn jwks(jwt: Jwt, res: &mut Response) -> AppResult<()> {
+    info!("[HANDLER] Entering jwks");
+    res.render(Json(jwt.jwks()));
+    Ok(())
+}
+
+/// 由 PEM 公钥（SPKI `PUBLIC KEY` 或 PKCS#1 `RSA PUBLIC KEY`）生成 JWK
+pub(crate) fn public_jwk(kid: &str, algorithm: Algorithm, public_pem: &[u8]) -> AppResult<Jwk> {
+    let pem = pem::parse(public_pem).map_err(|e| key_error(kid, e))?;
+    let key_bits = match pem.tag() {
+        "RSA PUBLIC KEY" => pem.contents().to_vec(),
+        "PUBLIC KEY" => match from_der(pem.contents())
+            .map_err(|e| key_error(kid, e))?
+            .as_slice()
+        {
+            [ASN1Block::Sequence(_, spki)] => match spki.as_slice() {
+                [_, ASN1Block::BitString(_, _, bits)] => bits.clone(),
+                _ => return Err(key_error(kid, "invalid SubjectPublicKeyInfo")),
+            },
+            _ => return Err(key_error(kid, "invalid SubjectPublicKeyInfo")),
+        },
+        tag => return Err(key_error(kid, format!("unsupported pem tag: {tag}"))),
+    };
+
+    let algorithm_params = match algorithm {
+        Algorithm::RS256
+        | Algorithm::RS384
+        | Algorithm::RS512
+        | Algorithm::PS256
+        | Algorithm::PS384
+        | Algorithm::PS512 => match from_der(&key_bits)
+            .map_err(|e| key_error(kid, e))?
+            .as_slice()
+        {
+            [ASN1Block::Sequence(_, rsa)] => match rsa.as_slice() {
+                [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] => {
+                    AlgorithmParameters::RSA(RSAKeyParameters {
+                        n: URL_SAFE_NO_PAD.encode(n.to_bytes_be().1),
+                        e: URL_SAFE_NO_PAD.encode(e.to_bytes_be().1),
+                        ..Default::default()
+                    })
+                }
+                _ => return Err(key_error(kid, "invalid RSA public key")),
+            },
+            _ => return Err(key_error(kid, "invalid RSA public key")),
+        },
+        Algorithm::ES256 | Algorithm::ES384 => {
+            // 未压缩的椭圆曲线点：0x04 || x || y
+            let point = match key_bits.split_first() {
+                Some((0x04, point)) if point.len() % 2 == 0 => point,
+                _ => return Err(key_error(kid, "invalid EC public key")),
+            };
+            let (x, y) = point.split_at(point.len() / 2);
+            AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                curve: if algorithm == Algorithm::ES256 {
+                    EllipticCurve::P256
+                } else {
+                    EllipticCurve::P384
+                },
+                x: URL_SAFE_NO_PAD.encode(x),
+                y: URL_SAFE_NO_PAD.encode(y),
+                ..Default::default()
+            })
+        }
+        Algorithm::EdDSA => AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+            curve: EllipticCurve::Ed25519,
+            x: URL_SAFE_NO_PAD.encode(&key_bits),
+            ..Default::default()
+        }),
+        _ => {
+            return Err(key_error(
+                kid,
+                format!("unsupported algorithm: {algorithm:?}"),
+            ));
+        }
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(
+                format!("{algorithm:?}")
+                    .parse::<KeyAlgorithm>()
+                    .map_err(|e| key_error(kid, e))?,
+            ),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: algorithm_params,
+    })
+}
+
+fn key_error(kid: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::Other(format!("JWT 密钥 {kid} 解析失败: {e}"))
+}
+
+#[cfg(test)]
+mod test {
+    use jsonwebtoken::{Algorithm, jwk::AlgorithmParameters};
+
+    use crate::jwks::public_jwk;
+
+    const ED25519_PUBLIC_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VwAyEAGb9ECWmEzf6FQbrBZ9w7lshQhqowtrbLDFw4rXAxZuE=
+-----END PUBLIC KEY-----";
+
+    #[test]
+    fn public_jwk_test() {
+        let jwk = public_jwk("ed-1", Algorithm::EdDSA, ED25519_PUBLIC_PEM.as_bytes()).unwrap();
+        assert_eq!(jwk.common.key_id.as_deref(), Some("ed-1"));
+        match jwk.algorithm {
+            AlgorithmParameters::OctetKeyPair(params) => {
+                assert_eq!(params.x, "Gb9ECWmEzf6FQbrBZ9w7lshQhqowtrbLDFw4rXAxZuE")
+            }
+            _ => panic!("unexpected key type"),
+        }
+        assert!(public_jwk("ed-1", Algorithm::HS256, ED25519_PUBLIC_PEM.as_bytes()).is_err());
+    }
+}
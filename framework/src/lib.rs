@@ -1,7 +1,49 @@
+pub mod api_key;
+pub mod background;
+pub mod client_ip;
 pub mod config;
+pub mod cors;
 pub mod db;
+pub mod ip_location;
+pub mod jwks;
 pub mod jwt;
 pub mod log;
+pub mod login_lock;
+pub mod metrics;
 pub mod midddleware;
+pub mod migrate;
+pub mod oidc;
+pub mod password;
+pub mod permission;
+pub mod rate_limit;
+pub mod request_id;
+pub mod session;
+pub mod state;
 
 pub use crate::config::Setting;
+
+use common::AppResult;
+
+use crate::{
+    background::BackgroundTasks, client_ip::ClientIpTool, cors::CorsPolicy,
+    cors::SecurityHeadersTool, ip_location::IpLocationTool, login_lock::LoginLockTool,
+    metrics::MetricsTool, oidc::OidcTool, password::PasswordPolicy, rate_limit::RateLimiter,
+    state::AppState,
+};
+
+/// 按配置添加框架提供的工具：后台任务、指标、跨域、安全响应头、限流、客户端地址解析、
+/// 登录锁定、密码策略、IP 归属地及单点登录
+pub fn init_state(state: AppState) -> AppResult<AppState> {
+    let config = state.config().clone();
+    Ok(state
+        .with(BackgroundTasks::default())
+        .with(MetricsTool::new(config.metrics))
+        .with(CorsPolicy::new(config.cors))
+        .with(SecurityHeadersTool::new(config.security_headers))
+        .with(RateLimiter::new(config.rate_limit))
+        .with(ClientIpTool::new(config.proxy)?)
+        .with(LoginLockTool::new(config.login))
+        .with(PasswordPolicy::new(config.password)?)
+        .with(IpLocationTool::new(config.ip_location)?)
+        .with(OidcTool::new(config.oidc)?))
+}
@@ -1,6 +1,418 @@
-pub fn init_tracing() {
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .with_target(false)
-        .init();
+//! 日志：输出到控制台及按天/按大小滚动的日志文件，运行时可调整日志级别
+
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use common::{AppError, AppResult};
+use serde_json::{Map, Value};
+use time::{Date, OffsetDateTime, format_description::well_known::Rfc3339};
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+    info,
+    level_filters::LevelFilter,
+    span,
+};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    field::RecordFields,
+    fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields, MakeWriter, format::Writer},
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    reload,
+    util::SubscriberInitExt,
+};
+
+use crate::config::{Log, LogFormat, LogRotation};
+
+/// 日志工具，持有日志过滤器的重载句柄
+#[derive(Clone)]
+pub struct LogTool {
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogTool {
+    /// 当前生效的日志过滤指令
+    pub fn level(&self) -> AppResult<String> {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .map_err(|e| AppError::Other(e.to_string()))
+    }
+
+    /// 修改日志级别，重启后恢复为配置文件中的级别
+    pub fn set_level(&self, directives: &str) -> AppResult<()> {
+        let filter = EnvFilter::try_new(directives)
+            .map_err(|e| AppError::ValidationFailed(format!("日志级别格式错误: {e}")))?;
+        self.handle
+            .reload(filter)
+            .map_err(|e| AppError::Other(e.to_string()))?;
+        info!("[LOG] Log level changed to: {}", directives);
+        Ok(())
+    }
+}
+
+/// 日志初始化之前使用的临时日志：`f` 执行期间输出到标准错误，加载配置等步骤的日志不会丢失
+pub fn bootstrap<T>(f: impl FnOnce() -> T) -> T {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .finish();
+    tracing::subscriber::with_default(subscriber, f)
+}
+
+/// 初始化日志，`RUST_LOG` 环境变量优先于配置的日志级别，返回用于运行时调整级别的 [`LogTool`]
+pub fn init_tracing(config: &Log) -> AppResult<LogTool> {
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&config.level))
+        .map_err(|e| AppError::Other(format!("日志级别 {} 格式错误: {e}", config.level)))?;
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let mut layers = Vec::new();
+    if config.console {
+        layers.push(output_layer(config.format, io::stdout, true));
+    }
+    if !config.dir.is_empty() {
+        let dir = Path::new(&config.dir);
+        let file = RollingFile::new(dir, &config.file_name, config)
+            .map_err(|e| AppError::Other(format!("日志文件创建失败: {e}")))?;
+        layers.push(output_layer(config.format, Arc::new(file), false));
+        if !config.error_file_name.is_empty() {
+            let file = RollingFile::new(dir, &config.error_file_name, config)
+                .map_err(|e| AppError::Other(format!("错误日志文件创建失败: {e}")))?;
+            layers.push(
+                output_layer(config.format, Arc::new(file), false)
+                    .with_filter(LevelFilter::ERROR)
+                    .boxed(),
+            );
+        }
+    }
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layers)
+        .try_init()
+        .map_err(|e| AppError::Other(format!("日志初始化失败: {e}")))?;
+    Ok(LogTool { handle })
+}
+
+fn output_layer<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi)
+        .with_target(false);
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Pretty => layer.pretty().boxed(),
+        LogFormat::Json => layer
+            .event_format(JsonFormat)
+            .fmt_fields(JsonFields)
+            .boxed(),
+    }
+}
+
+/// 滚动日志文件：当前文件为 `{file_name}`，滚动后重命名为 `{file_name}.{日期}.{序号}`
+pub struct RollingFile {
+    dir: PathBuf,
+    file_name: String,
+    rotation: LogRotation,
+    max_size: u64,
+    max_files: usize,
+    state: Mutex<RollingState>,
+}
+
+struct RollingState {
+    file: File,
+    size: u64,
+    /// 当前文件内容所属的日期
+    date: Date,
+}
+
+impl RollingFile {
+    pub fn new(dir: &Path, file_name: &str, config: &Log) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(file_name);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+        let date = metadata
+            .modified()
+            .map(|time| OffsetDateTime::from(time).date())
+            .unwrap_or_else(|_| OffsetDateTime::now_utc().date());
+        let rolling = Self {
+            dir: dir.to_path_buf(),
+            file_name: file_name.to_string(),
+            rotation: config.rotation,
+            max_size: config.max_size_mb * 1024 * 1024,
+            max_files: config.max_files,
+            state: Mutex::new(RollingState {
+                file,
+                size: metadata.len(),
+                date,
+            }),
+        };
+        rolling.cleanup();
+        Ok(rolling)
+    }
+
+    fn should_rotate(&self, state: &RollingState, today: Date, incoming: usize) -> bool {
+        match self.rotation {
+            LogRotation::Daily => state.date != today,
+            LogRotation::Size => {
+                self.max_size > 0 && state.size > 0 && state.size + incoming as u64 > self.max_size
+            }
+            LogRotation::Never => false,
+        }
+    }
+
+    fn rotate(&self, state: &mut RollingState, today: Date) -> io::Result<()> {
+        state.file.flush()?;
+        let prefix = format!("{}.{}", self.file_name, state.date);
+        let target = (1..)
+            .map(|n| self.dir.join(format!("{prefix}.{n}")))
+            .find(|path| !path.exists())
+            .unwrap_or_default();
+        fs::rename(self.dir.join(&self.file_name), target)?;
+        state.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(&self.file_name))?;
+        state.size = 0;
+        state.date = today;
+        self.cleanup();
+        Ok(())
+    }
+
+    /// 删除超出保留数量的历史文件（按修改时间从旧到新）
+    fn cleanup(&self) {
+        if self.max_files == 0 {
+            return;
+        }
+        let prefix = format!("{}.", self.file_name);
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut rotated: Vec<(SystemTime, PathBuf)> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name.starts_with(&prefix))
+            })
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect();
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.max_files);
+        for (_, path) in rotated.into_iter().take(excess) {
+            fs::remove_file(path).ok();
+        }
+    }
+}
+
+impl Write for &RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let today = OffsetDateTime::now_utc().date();
+        if self.should_rotate(&state, today, buf.len())
+            && let Err(e) = self.rotate(&mut state, today)
+        {
+            // 滚动失败时继续写入当前文件
+            eprintln!("[LOG] Failed to rotate {}: {}", self.file_name, e);
+        }
+        let written = state.file.write(buf)?;
+        state.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .file
+            .flush()
+    }
+}
+
+#[derive(Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl Visit for JsonVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}").into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+}
+
+/// span 字段以 JSON 对象的形式缓存，供 [`JsonFormat`] 输出
+struct JsonFields;
+
+impl<'w> FormatFields<'w> for JsonFields {
+    fn format_fields<R: RecordFields>(&self, mut writer: Writer<'w>, fields: R) -> fmt::Result {
+        let mut visitor = JsonVisitor::default();
+        fields.record(&mut visitor);
+        write!(writer, "{}", Value::Object(visitor.0))
+    }
+
+    fn add_fields(
+        &self,
+        current: &'w mut FormattedFields<Self>,
+        fields: &span::Record<'_>,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor(serde_json::from_str(&current.fields).unwrap_or_default());
+        fields.record(&mut visitor);
+        current.fields = Value::Object(visitor.0).to_string();
+        Ok(())
+    }
+}
+
+/// 每行一个 JSON 对象：时间、级别、目标、消息、其余字段及所在的 span
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut fields = JsonVisitor::default();
+        event.record(&mut fields);
+
+        let mut object = Map::new();
+        let timestamp = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .unwrap_or_default();
+        object.insert("timestamp".to_string(), timestamp.into());
+        object.insert("level".to_string(), metadata.level().as_str().into());
+        object.insert("target".to_string(), metadata.target().into());
+        if let Some(message) = fields.0.remove("message") {
+            object.insert("message".to_string(), message);
+        }
+        if !fields.0.is_empty() {
+            object.insert("fields".to_string(), Value::Object(fields.0));
+        }
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<Value> = scope
+                .from_root()
+                .map(|span| {
+                    let mut item: Map<String, Value> = span
+                        .extensions()
+                        .get::<FormattedFields<N>>()
+                        .and_then(|f| serde_json::from_str(&f.fields).ok())
+                        .unwrap_or_default();
+                    item.insert("name".to_string(), span.name().into());
+                    Value::Object(item)
+                })
+                .collect();
+            if !spans.is_empty() {
+                object.insert("spans".to_string(), spans.into());
+            }
+        }
+        writeln!(writer, "{}", Value::Object(object))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf, sync::Arc};
+
+    use serde_json::Value;
+    use tracing::{info, info_span};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::{RollingFile, output_layer};
+    use crate::config::{Log, LogFormat, LogRotation};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", uuid::Uuid::new_v4().simple()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn json_format_test() {
+        let dir = temp_dir("log-json");
+        let file = RollingFile::new(&dir, "app.log", &Log::default()).unwrap();
+        let subscriber = tracing_subscriber::registry().with(output_layer(
+            LogFormat::Json,
+            Arc::new(file),
+            false,
+        ));
+        tracing::subscriber::with_default(subscriber, || {
+            let span = info_span!("request", id = "abc");
+            let _guard = span.enter();
+            info!(user_id = 1, "hello");
+        });
+        let content = fs::read_to_string(dir.join("app.log")).unwrap();
+        let line: Value = serde_json::from_str(content.trim()).unwrap();
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["message"], "hello");
+        assert_eq!(line["fields"]["user_id"], 1);
+        assert_eq!(line["spans"][0]["name"], "request");
+        assert_eq!(line["spans"][0]["id"], "abc");
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn rolling_file_test() {
+        let dir = temp_dir("log-rolling");
+        let config = Log {
+            rotation: LogRotation::Size,
+            max_size_mb: 0,
+            max_files: 2,
+            ..Default::default()
+        };
+        let mut file = RollingFile::new(&dir, "app.log", &config).unwrap();
+        // 1 KB 上限
+        file.max_size = 1024;
+        let line = [b'a'; 600];
+        for _ in 0..5 {
+            std::io::Write::write_all(&mut &file, &line).unwrap();
+        }
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        // 每个文件只能容纳一行，共滚动 4 次，只保留最近 2 个历史文件
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[0], "app.log");
+        assert!(names[1..].iter().all(|n| n.starts_with("app.log.")));
+        assert_eq!(fs::metadata(dir.join("app.log")).unwrap().len(), 600);
+        fs::remove_dir_all(dir).ok();
+    }
 }
@@ -0,0 +1,146 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use common::{AppError, AppResult};
+use moka::{Expiry, future::Cache};
+use time::OffsetDateTime;
+use tracing::warn;
+
+use crate::config::Login;
+
+/// 失败计数过期策略：窗口从第一次失败开始计算，后续失败不延长窗口
+struct FixedWindow(Duration);
+impl Expiry<String, u32> for FixedWindow {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        _value: &u32,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(self.0)
+    }
+}
+
+/// 登录锁定工具：统计窗口内的登录失败次数，超过阈值后临时锁定用户名或IP，克隆后共享计数
+#[derive(Clone)]
+pub struct LoginLockTool {
+    config: Arc<Login>,
+    /// 失败计数，key 为 `user:{用户名}` 或 `ip:{IP}`
+    failures: Cache<String, u32>,
+    /// 锁定记录，key 同上，value 为解锁时间
+    locks: Cache<String, OffsetDateTime>,
+}
+
+impl LoginLockTool {
+    pub fn new(config: Login) -> Self {
+        Self {
+            failures: Cache::builder()
+                .max_capacity(config.max_capacity)
+                .expire_after(FixedWindow(Duration::from_secs(config.window_minutes * 60)))
+                .build(),
+            locks: Cache::builder()
+                .max_capacity(config.max_capacity)
+                .time_to_live(Duration::from_secs(config.lock_minutes * 60))
+                .build(),
+            config: Arc::new(config),
+        }
+    }
+
+    /// 校验用户名及IP是否处于锁定状态
+    pub async fn check(&self, username: &str, ipaddr: &str) -> AppResult<()> {
+        for key in Self::keys(username, ipaddr) {
+            if let Some(unlock_time) = self.locks.get(&key).await {
+                let remaining = (unlock_time - OffsetDateTime::now_utc()).whole_minutes() + 1;
+                return Err(AppError::AccountLocked(remaining.max(1) as u64));
+            }
+        }
+        Ok(())
+    }
+
+    /// 记录一次登录失败，达到阈值时锁定
+    pub async fn record_failure(&self, username: &str, ipaddr: &str) {
+        let thresholds = [self.config.max_retry_user, self.config.max_retry_ip];
+        for (key, max_retry) in Self::keys(username, ipaddr).into_iter().zip(thresholds) {
+            // 阈值为 0 表示不限制
+            if max_retry == 0 {
+                continue;
+            }
+            let failures = self
+                .failures
+                .entry(key.clone())
+                .and_upsert_with(|entry| {
+                    std::future::ready(entry.map(|e| e.into_value()).unwrap_or(0) + 1)
+                })
+                .await
+                .into_value();
+            if failures >= max_retry {
+                warn!("[LOGIN_LOCK] {} locked after {} failures", key, failures);
+                let unlock_time =
+                    OffsetDateTime::now_utc() + Duration::from_secs(self.config.lock_minutes * 60);
+                self.failures.invalidate(&key).await;
+                self.locks.insert(key, unlock_time).await;
+            }
+        }
+    }
+
+    /// 登录成功后清除该用户名的失败计数
+    pub async fn reset(&self, username: &str) {
+        self.failures.invalidate(&format!("user:{username}")).await;
+    }
+
+    /// 解除用户名锁定，返回解除前是否处于锁定状态
+    pub async fn unlock(&self, username: &str) -> bool {
+        self.remove(&format!("user:{username}")).await
+    }
+
+    /// 解除IP锁定，返回解除前是否处于锁定状态
+    pub async fn unlock_ip(&self, ipaddr: &str) -> bool {
+        self.remove(&format!("ip:{ipaddr}")).await
+    }
+
+    async fn remove(&self, key: &str) -> bool {
+        self.failures.invalidate(key).await;
+        self.locks.remove(key).await.is_some()
+    }
+
+    fn keys(username: &str, ipaddr: &str) -> Vec<String> {
+        let mut keys = vec![format!("user:{username}")];
+        if !ipaddr.is_empty() {
+            keys.push(format!("ip:{ipaddr}"));
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{config::Login, login_lock::LoginLockTool};
+
+    #[tokio::test]
+    async fn lock_test() {
+        let tool = LoginLockTool::new(Login {
+            max_retry_user: 2,
+            max_retry_ip: 3,
+            ..Default::default()
+        });
+        tool.record_failure("admin", "10.0.0.1").await;
+        assert!(tool.check("admin", "10.0.0.1").await.is_ok());
+        tool.record_failure("admin", "10.0.0.1").await;
+        assert!(tool.check("admin", "10.0.0.2").await.is_err());
+
+        // IP 达到阈值后，该 IP 上的其他用户同样被锁定
+        tool.record_failure("guest", "10.0.0.1").await;
+        assert!(tool.check("guest", "10.0.0.1").await.is_err());
+        assert!(tool.check("guest", "10.0.0.2").await.is_ok());
+
+        assert!(tool.unlock("admin").await);
+        assert!(tool.check("admin", "10.0.0.2").await.is_ok());
+
+        assert!(tool.check("admin", "10.0.0.1").await.is_err());
+        assert!(tool.unlock_ip("10.0.0.1").await);
+        assert!(tool.check("admin", "10.0.0.1").await.is_ok());
+        assert!(!tool.unlock_ip("10.0.0.1").await);
+    }
+}
@@ -0,0 +1,376 @@
+//! Prometheus 指标，以文本格式在 `/metrics` 输出
+//!
+//! 指标在使用处以静态变量声明，如
+//! `static LOGIN_TOTAL: Counter = Counter::new("login_total", "登录次数");`，
+//! 首次记录时注册到应用状态中的 [`MetricsTool`]。
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use common::{AppError, AppResult};
+use salvo::{
+    Request, Response, Writer, handler,
+    http::{
+        StatusCode,
+        header::{AUTHORIZATION, CONTENT_TYPE},
+    },
+    routing::PathParams,
+};
+use sqlx::PgPool;
+
+use crate::{
+    config::Metrics,
+    state::{Db, Ext},
+};
+
+/// 直方图默认分桶，单位：秒
+const DEFAULT_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+pub static HTTP_REQUESTS_TOTAL: Counter = Counter::new("http_requests_total", "HTTP 请求数");
+pub static HTTP_REQUEST_DURATION: Histogram = Histogram::new(
+    "http_request_duration_seconds",
+    "HTTP 请求处理耗时，单位：秒",
+);
+static DB_POOL_CONNECTIONS: Gauge = Gauge::new("db_pool_connections", "数据库连接池连接数");
+static DB_POOL_MAX_CONNECTIONS: Gauge =
+    Gauge::new("db_pool_max_connections", "数据库连接池最大连接数");
+static DB_POOL_ACQUIRE_WAIT: Gauge = Gauge::new(
+    "db_pool_acquire_wait_seconds",
+    "采集时从连接池获取连接的等待时间，单位：秒",
+);
+
+/// 数据库连接获取超时时间（采集时）
+const ACQUIRE_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl Kind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Counter => "counter",
+            Kind::Gauge => "gauge",
+            Kind::Histogram => "histogram",
+        }
+    }
+}
+
+enum Series {
+    Value(f64),
+    Histogram {
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+struct Family {
+    help: &'static str,
+    kind: Kind,
+    series: BTreeMap<Labels, Series>,
+}
+
+/// 计数器
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+}
+
+impl Counter {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help }
+    }
+
+    pub fn inc(&self, registry: &MetricsTool, labels: &[(&'static str, &str)]) {
+        registry.update(self.name, self.help, Kind::Counter, labels, |series| {
+            if let Series::Value(value) = series {
+                *value += 1.0;
+            }
+        });
+    }
+}
+
+/// 仪表盘，可增可减
+pub struct Gauge {
+    name: &'static str,
+    help: &'static str,
+}
+
+impl Gauge {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help }
+    }
+
+    pub fn set(&self, registry: &MetricsTool, labels: &[(&'static str, &str)], value: f64) {
+        registry.update(self.name, self.help, Kind::Gauge, labels, |series| {
+            *series = Series::Value(value);
+        });
+    }
+
+    pub fn add(&self, registry: &MetricsTool, labels: &[(&'static str, &str)], delta: f64) {
+        registry.update(self.name, self.help, Kind::Gauge, labels, |series| {
+            if let Series::Value(value) = series {
+                *value += delta;
+            }
+        });
+    }
+}
+
+/// 直方图，使用 [`DEFAULT_BUCKETS`] 分桶
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+}
+
+impl Histogram {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help }
+    }
+
+    pub fn observe(&self, registry: &MetricsTool, labels: &[(&'static str, &str)], value: f64) {
+        registry.update(self.name, self.help, Kind::Histogram, labels, |series| {
+            if let Series::Histogram {
+                buckets,
+                sum,
+                count,
+            } = series
+            {
+                for (bucket, bound) in buckets.iter_mut().zip(DEFAULT_BUCKETS) {
+                    if value <= bound {
+                        *bucket += 1;
+                    }
+                }
+                *sum += value;
+                *count += 1;
+            }
+        });
+    }
+}
+
+/// 指标注册表，克隆后共享同一组指标
+#[derive(Clone)]
+pub struct MetricsTool {
+    config: Arc<Metrics>,
+    families: Arc<Mutex<BTreeMap<&'static str, Family>>>,
+}
+
+impl MetricsTool {
+    pub fn new(config: Metrics) -> Self {
+        Self {
+            config: Arc::new(config),
+            families: Arc::default(),
+        }
+    }
+
+    fn update(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        kind: Kind,
+        labels: &[(&'static str, &str)],
+        f: impl FnOnce(&mut Series),
+    ) {
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let family = families.entry(name).or_insert_with(|| Family {
+            help,
+            kind,
+            series: BTreeMap::new(),
+        });
+        let labels = labels.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        let series = family.series.entry(labels).or_insert_with(|| match kind {
+            Kind::Histogram => Series::Histogram {
+                buckets: vec![0; DEFAULT_BUCKETS.len()],
+                sum: 0.0,
+                count: 0,
+            },
+            _ => Series::Value(0.0),
+        });
+        f(series);
+    }
+
+    /// 以 Prometheus 文本格式输出所有指标
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        for (name, family) in families.iter() {
+            writeln!(out, "# HELP {} {}", name, family.help).ok();
+            writeln!(out, "# TYPE {} {}", name, family.kind.as_str()).ok();
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Value(value) => {
+                        writeln!(out, "{}{} {}", name, format_labels(labels, None), value).ok();
+                    }
+                    Series::Histogram {
+                        buckets,
+                        sum,
+                        count,
+                    } => {
+                        for (bucket, bound) in buckets.iter().zip(DEFAULT_BUCKETS) {
+                            let le = bound.to_string();
+                            let labels = format_labels(labels, Some(&le));
+                            writeln!(out, "{name}_bucket{labels} {bucket}").ok();
+                        }
+                        let inf = format_labels(labels, Some("+Inf"));
+                        writeln!(out, "{name}_bucket{inf} {count}").ok();
+                        let labels = format_labels(labels, None);
+                        writeln!(out, "{name}_sum{labels} {sum}").ok();
+                        writeln!(out, "{name}_count{labels} {count}").ok();
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// 将请求路径还原为路由模板，如 `/sys/user/5` -> `/sys/user/{user_id}`，避免标签基数过高
+pub fn route_template(path: &str, params: &PathParams) -> String {
+    let mut path = path.to_string();
+    // 通配参数可能包含多段，整体替换
+    for (name, value) in params.iter() {
+        if value.contains('/') && path.ends_with(value.as_str()) {
+            path.truncate(path.len() - value.len());
+            path.push_str(&format!("{{**{name}}}"));
+        }
+    }
+    path.split('/')
+        .map(|segment| {
+            match params
+                .iter()
+                .find(|(_, value)| !segment.is_empty() && value.as_str() == segment)
+            {
+                Some((name, _)) => format!("{{{name}}}"),
+                None => segment.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 采集数据库连接池指标
+async fn collect_db_pool(registry: &MetricsTool, pool: &PgPool) {
+    let idle = pool.num_idle() as f64;
+    let size = pool.size() as f64;
+    DB_POOL_CONNECTIONS.set(registry, &[("state", "idle")], idle);
+    DB_POOL_CONNECTIONS.set(registry, &[("state", "active")], size - idle);
+    DB_POOL_MAX_CONNECTIONS.set(registry, &[], pool.options().get_max_connections() as f64);
+    let start = Instant::now();
+    let wait = match tokio::time::timeout(ACQUIRE_PROBE_TIMEOUT, pool.acquire()).await {
+        Ok(Ok(_conn)) => start.elapsed(),
+        _ => ACQUIRE_PROBE_TIMEOUT,
+    };
+    DB_POOL_ACQUIRE_WAIT.set(registry, &[], wait.as_secs_f64());
+}
+
+/// Prometheus 指标采集端点，配置了令牌时需携带 `Authorization: Bearer {token}`
+#[handler]
+pub async fn metrics(
+    db: Db,
+    registry: Ext<MetricsTool>,
+    req: &mut Request,
+    res: &mut Response,
+) -> AppResult<()> {
+    let config = &registry.config;
+    if !config.enabled {
+        res.status_code(StatusCode::NOT_FOUND);
+        return Ok(());
+    }
+    if !config.token.is_empty() {
+        let token = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if token != Some(config.token.as_str()) {
+            return Err(AppError::TokenInvalid);
+        }
+    }
+    collect_db_pool(&registry, &db).await;
+    res.add_header(
+        CONTENT_TYPE,
+        "text/plain; version=0.0.4; charset=utf-8",
+        true,
+    )
+    .ok();
+    res.render(registry.render());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use salvo::routing::PathParams;
+
+    use super::{Counter, Histogram, MetricsTool, route_template};
+    use crate::config::Metrics;
+
+    #[test]
+    fn route_template_test() {
+        let mut params = PathParams::new();
+        params.insert("user_id", "5".to_string());
+        assert_eq!(
+            route_template("/sys/user/5", &params),
+            "/sys/user/{user_id}"
+        );
+        assert_eq!(route_template("/sys/user/list", &params), "/sys/user/list");
+
+        let mut params = PathParams::new();
+        params.insert("**rest", "a/b/c".to_string());
+        assert_eq!(route_template("/x/a/b/c", &params), "/x/{**rest}");
+    }
+
+    #[test]
+    fn render_test() {
+        static TEST_TOTAL: Counter = Counter::new("test_total", "测试计数");
+        static TEST_SECONDS: Histogram = Histogram::new("test_seconds", "测试耗时");
+        let metrics = MetricsTool::new(Metrics::default());
+        TEST_TOTAL.inc(&metrics, &[("result", "ok")]);
+        TEST_TOTAL.inc(&metrics, &[("result", "ok")]);
+        TEST_TOTAL.inc(&metrics, &[("result", "say \"hi\"")]);
+        TEST_SECONDS.observe(&metrics, &[], 0.2);
+        TEST_SECONDS.observe(&metrics, &[], 3.0);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE test_total counter"));
+        assert!(text.contains("test_total{result=\"ok\"} 2"));
+        assert!(text.contains("test_total{result=\"say \\\"hi\\\"\"} 1"));
+        assert!(text.contains("test_seconds_bucket{le=\"0.1\"} 0"));
+        assert!(text.contains("test_seconds_bucket{le=\"0.25\"} 1"));
+        assert!(text.contains("test_seconds_bucket{le=\"+Inf\"} 2"));
+        assert!(text.contains("test_seconds_sum 3.2"));
+        assert!(text.contains("test_seconds_count 2"));
+    }
+}
@@ -1,8 +1,135 @@
-use common::AppResult;
-use salvo::{Depot, FlowCtrl, Request, Response, handler};
+use std::time::Instant;
 
-use crate::jwt::{CLAIMS, JWTTool};
+use common::{
+    AppError, AppResult,
+    constants::{Permission, REQUEST_ID_HEADER},
+};
+use salvo::{Depot, FlowCtrl, Request, Response, handler, http::StatusCode};
+use tracing::{Instrument, info_span, warn};
 
+use crate::{
+    api_key::{API_KEY, API_KEY_HEADER, ApiKeyAuth, ApiKeyTool},
+    cors::{CorsPolicy, SecurityHeadersTool},
+    jwt::{CLAIMS, Claims, TokenType},
+    metrics::{HTTP_REQUEST_DURATION, HTTP_REQUESTS_TOTAL, MetricsTool, route_template},
+    permission::PermTool,
+    rate_limit::RateLimiter,
+    request_id::{REQUEST_ID, resolve_request_id},
+    session::SessionCache,
+    state::AppState,
+};
+
+/// 请求编号中间件：需挂在最外层，后续处理的日志都记录在带请求编号的 span 中，
+/// 请求编号同时写入响应头和 Depot
+#[handler]
+pub async fn request_id(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) {
+    let id = resolve_request_id(req);
+    let span = info_span!(
+        "request",
+        id = %id,
+        method = %req.method(),
+        path = %req.uri().path()
+    );
+    res.add_header(REQUEST_ID_HEADER, id.as_str(), true).ok();
+    depot.insert(REQUEST_ID, id);
+    ctrl.call_next(req, depot, res).instrument(span).await;
+}
+
+/// HTTP 请求指标中间件：按请求方式、路由模板和状态码统计请求数及耗时
+#[handler]
+pub async fn http_metrics(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) {
+    let start = Instant::now();
+    ctrl.call_next(req, depot, res).await;
+    let method = req.method().to_string();
+    let route = route_template(req.uri().path(), req.params());
+    let status = res.status_code.unwrap_or(StatusCode::OK);
+    let labels = [
+        ("method", method.as_str()),
+        ("route", route.as_str()),
+        ("status", status.as_str()),
+    ];
+    if let Ok(metrics) = AppState::from_depot(depot).and_then(|state| state.ext::<MetricsTool>()) {
+        HTTP_REQUESTS_TOTAL.inc(metrics, &labels);
+        HTTP_REQUEST_DURATION.observe(metrics, &labels, start.elapsed().as_secs_f64());
+    }
+}
+
+/// 跨域中间件：预检请求直接应答，其余跨域请求添加跨域响应头
+#[handler]
+pub async fn cors(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) -> AppResult<()> {
+    let policy = AppState::from_depot(depot)?.ext::<CorsPolicy>()?;
+    if policy.enabled() && policy.apply(req, res) {
+        ctrl.skip_rest();
+    }
+    Ok(())
+}
+
+/// 预检请求兜底路由：让任意路径的 OPTIONS 请求都能匹配到路由，从而经过 `cors` 中间件
+#[handler]
+pub async fn preflight(res: &mut Response) {
+    res.status_code(StatusCode::NO_CONTENT);
+}
+
+/// 限流中间件：按路由规则从令牌桶取令牌，超限时返回 429
+#[handler]
+pub async fn rate_limit(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) -> AppResult<()> {
+    let limiter = AppState::from_depot(depot)?.ext::<RateLimiter>()?;
+    if !limiter.enabled() {
+        return Ok(());
+    }
+    let Some(rule) = limiter.rule(req.uri().path()) else {
+        return Ok(());
+    };
+    let key = RateLimiter::key(rule, req);
+    let state = limiter.acquire(rule, &key).await;
+    state.write_headers(res);
+    if let Some(seconds) = state.retry_after {
+        warn!(
+            "[RATE_LIMIT] {} exceeded limit of {}",
+            key,
+            req.uri().path()
+        );
+        ctrl.skip_rest();
+        return Err(AppError::TooManyRequests(seconds));
+    }
+    Ok(())
+}
+
+/// 安全响应头中间件
+#[handler]
+pub async fn security_headers(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> AppResult<()> {
+    let headers = AppState::from_depot(depot)?.ext::<SecurityHeadersTool>()?;
+    if headers.enabled() {
+        headers.apply(req.uri().path(), res);
+    }
+    Ok(())
+}
+
+/// 认证中间件：接受 Bearer 访问令牌，或 API Key（Bearer 或 `X-API-Key` 请求头）
 #[handler]
 pub async fn auth(
     req: &mut Request,
@@ -10,12 +137,99 @@ pub async fn auth(
     res: &mut Response,
     ctrl: &mut FlowCtrl,
 ) -> AppResult<()> {
-    let jwt_auth_util = JWTTool::get()?;
-    let token = jwt_auth_util.extract_token(req)?;
-    let claims = jwt_auth_util.verify_acc_token(&token)?;
-    depot.insert(CLAIMS, claims);
+    let state = AppState::from_depot(depot)?;
+    let jwt_auth_util = state.jwt();
+    let token = match req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(key) => key.to_string(),
+        None => jwt_auth_util.extract_token(req)?,
+    };
+    if ApiKeyTool::is_api_key(&token) {
+        let api_key = state.ext::<ApiKeyTool>()?.verify(&token).await?;
+        // API Key 没有会话，Claims 仅在本次请求内有效
+        let now = time::OffsetDateTime::now_utc().unix_timestamp() as u64;
+        let id = format!("apikey:{}", api_key.key_id);
+        depot.insert(
+            CLAIMS,
+            Claims {
+                sub: api_key.user_id,
+                exp: now,
+                iat: now,
+                iss: jwt_auth_util.issuer().to_string(),
+                jti: id.clone(),
+                sid: id,
+                token_type: TokenType::Access,
+            },
+        );
+        depot.insert(API_KEY, api_key);
+    } else {
+        let claims = jwt_auth_util.verify_acc_token(&token)?;
+        // 会话已被移除（登出、强退）
+        if !state.ext::<SessionCache>()?.contains(&claims.sid) {
+            return Err(AppError::TokenInvalid);
+        }
+        depot.insert(CLAIMS, claims);
+    }
     if ctrl.has_next() {
         ctrl.call_next(req, depot, res).await;
     }
     Ok(())
 }
+
+/// 仅限交互式登录会话的中间件，需挂在 `auth` 之后
+///
+/// 修改密码、双因素认证、绑定外部账号、会话及 API Key 管理等只能由本人操作，
+/// API Key 无论权限范围如何都不能访问。
+#[handler]
+pub async fn interactive_only(depot: &mut Depot) -> AppResult<()> {
+    if let Ok(api_key) = depot.get::<ApiKeyAuth>(API_KEY) {
+        warn!(
+            "[AUTH] api key: {} is not allowed on interactive routes",
+            api_key.key_id
+        );
+        return Err(AppError::PermissionDenied);
+    }
+    Ok(())
+}
+
+/// 权限校验中间件，需挂在 `auth` 之后（依赖 Depot 中的 Claims）
+pub struct RequirePerm(Permission);
+
+/// 创建权限校验中间件，如 `require_perm(Permission::UserAdd)`
+pub fn require_perm(perm: Permission) -> RequirePerm {
+    RequirePerm(perm)
+}
+
+#[handler]
+impl RequirePerm {
+    async fn handle(&self, depot: &mut Depot) -> AppResult<()> {
+        let user_id = depot
+            .get::<Claims>(CLAIMS)
+            .map(|c| c.sub)
+            .map_err(|_| AppError::TokenInvalid)?;
+        let perm_tool = AppState::from_depot(depot)?.ext::<PermTool>()?;
+        if !perm_tool.has_permission(user_id, self.0).await? {
+            warn!(
+                "[PERM] user_id: {} has no permission: {}",
+                user_id,
+                self.0.as_ref()
+            );
+            return Err(AppError::PermissionDenied);
+        }
+        // API Key 只能使用创建时选定的权限
+        if let Ok(api_key) = depot.get::<ApiKeyAuth>(API_KEY)
+            && !api_key.scopes.contains(self.0.as_ref())
+        {
+            warn!(
+                "[PERM] api key: {} is not scoped for permission: {}",
+                api_key.key_id,
+                self.0.as_ref()
+            );
+            return Err(AppError::PermissionDenied);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! 数据库迁移
+//!
+//! 迁移脚本位于仓库根目录 `migrations`，编译时嵌入二进制，
+//! 已执行的版本记录在 `_sqlx_migrations` 表（跟随连接的 `search_path`）。
+
+use common::{AppError, AppResult};
+use sqlx::{Pool, Postgres, migrate::Migrator};
+use tracing::{info, warn};
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+/// 迁移执行状态
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    /// 是否已执行
+    pub applied: bool,
+}
+
+/// 执行所有未执行的迁移
+pub async fn run(pool: &Pool<Postgres>) -> AppResult<()> {
+    MIGRATOR
+        .run(pool)
+        .await
+        .map_err(|e| AppError::Other(format!("数据库迁移失败: {e}")))?;
+    info!("[MIGRATE] Database schema is up to date");
+    Ok(())
+}
+
+/// 回滚版本号大于 `target` 的迁移，`target` 为 0 时回滚全部
+pub async fn undo(pool: &Pool<Postgres>, target: i64) -> AppResult<()> {
+    MIGRATOR
+        .undo(pool, target)
+        .await
+        .map_err(|e| AppError::Other(format!("数据库迁移回滚失败: {e}")))?;
+    info!("[MIGRATE] Database schema reverted to version {target}");
+    Ok(())
+}
+
+/// 二进制内嵌的迁移及其执行状态，按版本升序
+pub async fn status(pool: &Pool<Postgres>) -> AppResult<Vec<MigrationStatus>> {
+    let applied = applied_versions(pool).await?;
+    let status = MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| MigrationStatus {
+            version: m.version,
+            description: m.description.to_string(),
+            applied: applied
+                .iter()
+                .any(|(version, success)| *version == m.version && *success),
+        })
+        .collect();
+    Ok(status)
+}
+
+/// 检查数据库结构是否落后于代码，存在未执行或执行失败的迁移时返回错误
+pub async fn check(pool: &Pool<Postgres>) -> AppResult<()> {
+    let applied = applied_versions(pool).await?;
+    if let Some((version, _)) = applied.iter().find(|(_, success)| !success) {
+        return Err(AppError::Other(format!(
+            "数据库迁移 {version} 未成功完成，请修复后重新执行迁移"
+        )));
+    }
+    let pending: Vec<i64> = status(pool)
+        .await?
+        .into_iter()
+        .filter(|m| !m.applied)
+        .map(|m| m.version)
+        .collect();
+    if !pending.is_empty() {
+        return Err(AppError::Other(format!(
+            "数据库结构版本落后于代码，未执行的迁移: {pending:?}，请先执行迁移或开启 database.auto_migrate"
+        )));
+    }
+    if let Some((version, _)) = applied
+        .iter()
+        .find(|(version, _)| !MIGRATOR.version_exists(*version))
+    {
+        warn!("[MIGRATE] Database has migration {version} unknown to this build");
+    }
+    Ok(())
+}
+
+/// 已记录的迁移版本及是否成功，迁移表不存在时为空
+async fn applied_versions(pool: &Pool<Postgres>) -> AppResult<Vec<(i64, bool)>> {
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !exists {
+        return Ok(Vec::new());
+    }
+    let versions = sqlx::query_as("SELECT version, success FROM _sqlx_migrations ORDER BY version")
+        .fetch_all(pool)
+        .await?;
+    Ok(versions)
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::PgPool;
+
+    use super::{check, run, status, undo};
+
+    #[sqlx::test(migrations = false)]
+    async fn up_down_test(db: PgPool) -> anyhow::Result<()> {
+        assert!(check(&db).await.is_err());
+
+        run(&db).await?;
+        check(&db).await?;
+        assert!(status(&db).await?.iter().all(|m| m.applied));
+        let tables: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pg_tables WHERE schemaname = current_schema()",
+        )
+        .fetch_one(&db)
+        .await?;
+        // 业务表及 _sqlx_migrations
+        assert_eq!(tables, 19);
+
+        undo(&db, 0).await?;
+        assert!(status(&db).await?.iter().all(|m| !m.applied));
+        let tables: Vec<String> = sqlx::query_scalar(
+            "SELECT tablename::text FROM pg_tables WHERE schemaname = current_schema()",
+        )
+        .fetch_all(&db)
+        .await?;
+        assert_eq!(tables, vec!["_sqlx_migrations".to_string()]);
+
+        // 回滚后可以重新执行
+        run(&db).await?;
+        check(&db).await?;
+        Ok(())
+    }
+}
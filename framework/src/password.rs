@@ -0,0 +1,145 @@
+use std::{collections::HashSet, fs, sync::Arc, time::Duration};
+
+use common::{AppError, AppResult};
+use time::OffsetDateTime;
+use tracing::info;
+
+use crate::config::Password;
+
+/// 内置的常见弱密码（小写），可通过 `banned_file` 追加
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "111111",
+    "000000",
+    "123123",
+    "654321",
+    "password",
+    "password1",
+    "password123",
+    "passw0rd",
+    "p@ssw0rd",
+    "qwerty",
+    "qwerty123",
+    "qwertyuiop",
+    "abc123",
+    "abc12345",
+    "1q2w3e4r",
+    "1qaz2wsx",
+    "admin",
+    "admin123",
+    "admin@123",
+    "root",
+    "root123",
+    "letmein",
+    "welcome",
+    "iloveyou",
+    "a123456",
+    "aa123456",
+    "woaini1314",
+];
+
+/// 密码策略：复杂度、常见弱密码、历史密码数量与有效期
+#[derive(Clone)]
+pub struct PasswordPolicy {
+    config: Arc<Password>,
+    banned: Arc<HashSet<String>>,
+}
+
+impl PasswordPolicy {
+    pub fn new(config: Password) -> AppResult<Self> {
+        let mut banned: HashSet<String> = COMMON_PASSWORDS.iter().map(|p| p.to_string()).collect();
+        if let Some(path) = config.banned_file.as_deref().filter(|p| !p.is_empty()) {
+            let content = fs::read_to_string(path)
+                .map_err(|e| AppError::Other(format!("弱密码列表 {path} 读取失败: {e}")))?;
+            banned.extend(
+                content
+                    .lines()
+                    .map(|line| line.trim().to_lowercase())
+                    .filter(|line| !line.is_empty()),
+            );
+            info!("[PASSWORD] Loaded banned password list from {}", path);
+        }
+        Ok(Self {
+            config: Arc::new(config),
+            banned: Arc::new(banned),
+        })
+    }
+
+    /// 校验密码复杂度，不满足时返回所有未通过的规则
+    pub fn validate(&self, password: &str, user_name: &str) -> AppResult<()> {
+        let config = &self.config;
+        let mut failed = Vec::new();
+        let length = password.chars().count();
+        if length < config.min_length {
+            failed.push(format!("长度不能少于{}位", config.min_length));
+        }
+        if length > config.max_length {
+            failed.push(format!("长度不能超过{}位", config.max_length));
+        }
+        if config.require_uppercase && !password.chars().any(|c| c.is_ascii_uppercase()) {
+            failed.push("必须包含大写字母".to_string());
+        }
+        if config.require_lowercase && !password.chars().any(|c| c.is_ascii_lowercase()) {
+            failed.push("必须包含小写字母".to_string());
+        }
+        if config.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            failed.push("必须包含数字".to_string());
+        }
+        if config.require_special && password.chars().all(|c| c.is_ascii_alphanumeric()) {
+            failed.push("必须包含特殊字符".to_string());
+        }
+        let lowercase = password.to_lowercase();
+        if self.banned.contains(&lowercase) {
+            failed.push("不能使用常见弱密码".to_string());
+        }
+        if !user_name.is_empty() && lowercase.contains(&user_name.to_lowercase()) {
+            failed.push("不能包含用户名".to_string());
+        }
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::ValidationFailed(format!(
+                "密码不符合要求：{}",
+                failed.join("；")
+            )))
+        }
+    }
+
+    /// 需要校验的历史密码数量，0 表示不限制
+    pub fn history(&self) -> usize {
+        self.config.history
+    }
+
+    /// 密码是否已过期（以最后修改时间计算，有效期为 0 表示永不过期）
+    pub fn is_expired(&self, pwd_update_date: OffsetDateTime) -> bool {
+        self.config.expire_days > 0
+            && pwd_update_date + Duration::from_secs(self.config.expire_days * 24 * 3600)
+                <= OffsetDateTime::now_utc()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::AppError;
+
+    use crate::{config::Password, password::PasswordPolicy};
+
+    #[test]
+    fn validate_test() {
+        let policy = PasswordPolicy::new(Password::default()).unwrap();
+        assert!(policy.validate("Xk9#mPq2z", "admin").is_ok());
+        match policy.validate("abc", "admin") {
+            Err(AppError::ValidationFailed(msg)) => {
+                assert!(msg.contains("长度不能少于8位"));
+                assert!(msg.contains("必须包含大写字母"));
+                assert!(msg.contains("必须包含数字"));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(policy.validate("Password123", "admin").is_err());
+        assert!(policy.validate("Admin2024x", "admin").is_err());
+    }
+}
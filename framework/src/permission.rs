@@ -0,0 +1,97 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use common::{AppResult, constants::Permission};
+use moka::future::Cache;
+use tracing::info;
+
+/// 用户权限加载器
+///
+/// 权限数据来自业务模块（sys_menu / sys_role），framework 不能依赖业务模块，
+/// 所以由业务模块实现该 trait，并在启动时通过 [`crate::state::AppState::with`] 注册 [`PermTool`]。
+#[async_trait]
+pub trait PermissionLoader: Send + Sync {
+    /// 根据用户ID查询其拥有的权限标识列表
+    async fn load(&self, user_id: i32) -> AppResult<Vec<String>>;
+}
+
+/// 用户权限工具：加载并缓存用户的权限标识，克隆后共享同一缓存
+#[derive(Clone)]
+pub struct PermTool {
+    loader: Arc<dyn PermissionLoader>,
+    cache: Cache<i32, Arc<HashSet<String>>>,
+}
+
+impl PermTool {
+    pub fn new(loader: impl PermissionLoader + 'static) -> Self {
+        Self {
+            loader: Arc::new(loader),
+            cache: Cache::builder()
+                .max_capacity(1000)
+                .time_to_live(Duration::from_secs(300))
+                .build(),
+        }
+    }
+
+    /// 获取用户权限集合（优先读取缓存）
+    pub async fn permissions(&self, user_id: i32) -> AppResult<Arc<HashSet<String>>> {
+        if let Some(perms) = self.cache.get(&user_id).await {
+            return Ok(perms);
+        }
+        let perms: Arc<HashSet<String>> =
+            Arc::new(self.loader.load(user_id).await?.into_iter().collect());
+        info!(
+            "[PERM] Loaded {} permissions for user_id: {}",
+            perms.len(),
+            user_id
+        );
+        self.cache.insert(user_id, perms.clone()).await;
+        Ok(perms)
+    }
+
+    /// 判断用户是否拥有指定权限
+    pub async fn has_permission(&self, user_id: i32, perm: Permission) -> AppResult<bool> {
+        // 超级管理员拥有所有权限
+        if user_id == 1 {
+            return Ok(true);
+        }
+        let perms = self.permissions(user_id).await?;
+        Ok(perms.contains(perm.as_ref()))
+    }
+
+    /// 清除指定用户的权限缓存（用户角色变更时调用）
+    pub async fn invalidate(&self, user_id: i32) {
+        self.cache.invalidate(&user_id).await;
+    }
+
+    /// 清除所有用户的权限缓存（角色、菜单变更时调用）
+    pub fn invalidate_all(&self) {
+        self.cache.invalidate_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use async_trait::async_trait;
+    use common::{AppResult, constants::Permission};
+
+    use crate::permission::{PermTool, PermissionLoader};
+
+    struct FakeLoader;
+    #[async_trait]
+    impl PermissionLoader for FakeLoader {
+        async fn load(&self, _user_id: i32) -> AppResult<Vec<String>> {
+            Ok(vec!["system:user:list".to_string()])
+        }
+    }
+
+    #[tokio::test]
+    async fn has_permission_test() -> AppResult<()> {
+        let perm_tool = PermTool::new(FakeLoader);
+        assert!(perm_tool.has_permission(2, Permission::UserList).await?);
+        assert!(!perm_tool.has_permission(2, Permission::UserAdd).await?);
+        // 超级管理员
+        assert!(perm_tool.has_permission(1, Permission::UserAdd).await?);
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+//! 令牌桶限流
+//!
+//! 每条规则的桶容量为 `capacity`，令牌以 `capacity / period_secs` 的速率持续补充。
+//! 桶闲置一个周期后必然已补满，因此直接从缓存中淘汰。
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use moka::future::Cache;
+use salvo::{Request, Response, http::header::RETRY_AFTER};
+
+use crate::{
+    client_ip::client_ip,
+    config::{RateLimit, RateLimitKey, RateLimitRule},
+    state::AppState,
+};
+
+/// 同时保留的令牌桶数量上限
+const MAX_BUCKETS: u64 = 100_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// 一次限流判定的结果，对应 `RateLimit-*` 响应头
+#[derive(Debug, PartialEq, Eq)]
+pub struct RateLimitState {
+    pub limit: u32,
+    pub remaining: u32,
+    /// 桶补满所需秒数
+    pub reset: u64,
+    /// 被拒绝时需要等待的秒数
+    pub retry_after: Option<u64>,
+}
+
+impl RateLimitState {
+    pub fn write_headers(&self, res: &mut Response) {
+        res.add_header("RateLimit-Limit", self.limit, true).ok();
+        res.add_header("RateLimit-Remaining", self.remaining, true)
+            .ok();
+        res.add_header("RateLimit-Reset", self.reset, true).ok();
+        if let Some(seconds) = self.retry_after {
+            res.add_header(RETRY_AFTER, seconds, true).ok();
+        }
+    }
+}
+
+/// 限流工具，克隆后共享同一组令牌桶
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimit>,
+    buckets: Cache<String, Arc<Mutex<Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimit) -> Self {
+        let longest = config
+            .routes
+            .iter()
+            .chain([&config.default])
+            .map(|rule| rule.period_secs.max(1))
+            .max()
+            .unwrap_or(1);
+        Self {
+            buckets: Cache::builder()
+                .max_capacity(MAX_BUCKETS)
+                .time_to_idle(Duration::from_secs(longest))
+                .build(),
+            config: Arc::new(config),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// 匹配请求路径的规则，不限制时返回 `None`
+    pub fn rule(&self, path: &str) -> Option<&RateLimitRule> {
+        let rule = self
+            .config
+            .routes
+            .iter()
+            .find(|rule| {
+                path.strip_prefix(rule.path.as_str()).is_some_and(|rest| {
+                    rest.is_empty() || rest.starts_with('/') || rule.path.ends_with('/')
+                })
+            })
+            .unwrap_or(&self.config.default);
+        Some(rule).filter(|rule| rule.capacity > 0)
+    }
+
+    /// 按规则的限流维度识别请求来源
+    pub fn key(rule: &RateLimitRule, req: &Request) -> String {
+        match rule.key {
+            RateLimitKey::Route => "route".to_string(),
+            RateLimitKey::User => match request_user(req) {
+                Some(user_id) => format!("user:{user_id}"),
+                None => format!("ip:{}", client_ip(req)),
+            },
+            RateLimitKey::Ip => format!("ip:{}", client_ip(req)),
+        }
+    }
+
+    /// 从对应的令牌桶中取一个令牌
+    pub async fn acquire(&self, rule: &RateLimitRule, key: &str) -> RateLimitState {
+        let now = Instant::now();
+        let bucket = self
+            .buckets
+            .get_with(format!("{}|{}", rule.path, key), async {
+                Arc::new(Mutex::new(Bucket {
+                    tokens: rule.capacity as f64,
+                    updated: now,
+                }))
+            })
+            .await;
+        let mut bucket = bucket.lock().unwrap_or_else(|e| e.into_inner());
+        take(&mut bucket, rule, now)
+    }
+}
+
+/// 已登录用户的ID（此时认证中间件尚未执行，直接校验访问令牌）
+fn request_user(req: &Request) -> Option<i32> {
+    let jwt = AppState::from_request(req).ok()?.jwt();
+    let token = jwt.extract_token(req).ok()?;
+    jwt.verify_acc_token(&token).ok().map(|claims| claims.sub)
+}
+
+fn take(bucket: &mut Bucket, rule: &RateLimitRule, now: Instant) -> RateLimitState {
+    let capacity = rule.capacity as f64;
+    let rate = capacity / rule.period_secs.max(1) as f64;
+    let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+    bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+    bucket.updated = now;
+    let retry_after = if bucket.tokens >= 1.0 {
+        bucket.tokens -= 1.0;
+        None
+    } else {
+        Some((((1.0 - bucket.tokens) / rate).ceil() as u64).max(1))
+    };
+    RateLimitState {
+        limit: rule.capacity,
+        remaining: bucket.tokens.floor() as u32,
+        reset: ((capacity - bucket.tokens) / rate).ceil() as u64,
+        retry_after,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::{Bucket, RateLimiter, take};
+    use crate::config::{RateLimit, RateLimitRule};
+
+    #[test]
+    fn take_test() {
+        let rule = RateLimitRule {
+            capacity: 2,
+            period_secs: 10,
+            ..Default::default()
+        };
+        let now = Instant::now();
+        let mut bucket = Bucket {
+            tokens: 2.0,
+            updated: now,
+        };
+        let state = take(&mut bucket, &rule, now);
+        assert_eq!(
+            (state.remaining, state.reset, state.retry_after),
+            (1, 5, None)
+        );
+        take(&mut bucket, &rule, now);
+        let state = take(&mut bucket, &rule, now);
+        assert_eq!(
+            (state.remaining, state.reset, state.retry_after),
+            (0, 10, Some(5))
+        );
+        // 每 5 秒补充一个令牌
+        let state = take(&mut bucket, &rule, now + Duration::from_secs(5));
+        assert_eq!(state.retry_after, None);
+        let state = take(&mut bucket, &rule, now + Duration::from_secs(60));
+        assert_eq!((state.remaining, state.reset), (1, 5));
+    }
+
+    #[test]
+    fn rule_test() {
+        let limiter = RateLimiter::new(RateLimit::default());
+        assert_eq!(limiter.rule("/sys/login").unwrap().capacity, 10);
+        assert_eq!(limiter.rule("/sys/login/password").unwrap().capacity, 10);
+        assert_eq!(limiter.rule("/sys/catpcha").unwrap().capacity, 20);
+        // 默认规则不限制
+        assert!(limiter.rule("/sys/loginx").is_none());
+        assert!(limiter.rule("/sys/user/list").is_none());
+    }
+
+    #[tokio::test]
+    async fn acquire_test() {
+        let limiter = RateLimiter::new(RateLimit::default());
+        let rule = limiter.rule("/sys/login").unwrap();
+        for _ in 0..10 {
+            assert!(
+                limiter
+                    .acquire(rule, "ip:1.1.1.1")
+                    .await
+                    .retry_after
+                    .is_none()
+            );
+        }
+        assert!(
+            limiter
+                .acquire(rule, "ip:1.1.1.1")
+                .await
+                .retry_after
+                .is_some()
+        );
+        // 不同来源使用各自的令牌桶
+        assert!(
+            limiter
+                .acquire(rule, "ip:2.2.2.2")
+                .await
+                .retry_after
+                .is_none()
+        );
+    }
+}
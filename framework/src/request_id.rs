@@ -0,0 +1,56 @@
+//! 请求编号：沿用上游（网关、前端）传入的 `X-Request-Id`，否则生成新的编号
+
+use common::constants::REQUEST_ID_HEADER;
+use salvo::Request;
+use uuid::Uuid;
+
+/// Depot 中请求编号的 key
+pub const REQUEST_ID: &str = "request_id";
+
+const MAX_LEN: usize = 64;
+
+/// 获取请求编号，传入的编号仅允许字母、数字及 `-`、`_`、`.`，最长 64 位
+pub fn resolve_request_id(req: &Request) -> String {
+    req.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_LEN
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+        .map(String::from)
+        .unwrap_or_else(|| Uuid::new_v4().simple().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use salvo::Request;
+
+    use crate::request_id::resolve_request_id;
+
+    fn request(id: Option<&str>) -> Request {
+        let mut req = Request::default();
+        if let Some(id) = id {
+            req.headers_mut()
+                .insert("X-Request-Id", id.parse().unwrap());
+        }
+        req
+    }
+
+    #[test]
+    fn resolve_request_id_test() {
+        assert_eq!(
+            resolve_request_id(&request(Some("abc-123_x.y"))),
+            "abc-123_x.y"
+        );
+        assert_eq!(resolve_request_id(&request(None)).len(), 32);
+        // 非法字符或超长时重新生成
+        assert_ne!(resolve_request_id(&request(Some("a b"))), "a b");
+        let long = "a".repeat(65);
+        assert_ne!(resolve_request_id(&request(Some(&long))), long);
+    }
+}
@@ -0,0 +1,57 @@
+use std::{cmp::Reverse, time::Duration};
+
+use common::models::sys_user_online::SysUserOnline;
+use moka::future::Cache;
+
+use crate::jwt::JwtAuthUtil;
+
+/// 在线会话缓存，key 为会话编号（JWT 的 sid），克隆后共享同一缓存
+///
+/// 会话的存活时间与刷新令牌一致，刷新令牌时重新写入以续期；
+/// 会话被吊销（登出、强退、刷新令牌重放）后，该会话签发的令牌均失效。
+#[derive(Debug, Clone)]
+pub struct SessionCache {
+    cache: Cache<String, SysUserOnline>,
+}
+
+impl SessionCache {
+    pub fn new(ttl: Duration, max_capacity: u64) -> Self {
+        Self {
+            cache: Cache::builder()
+                .max_capacity(max_capacity)
+                .time_to_live(ttl)
+                .build(),
+        }
+    }
+
+    /// 记录（或续期）会话
+    pub async fn insert(&self, session: SysUserOnline) {
+        self.cache.insert(session.token_id.clone(), session).await;
+    }
+
+    pub async fn get(&self, token_id: &str) -> Option<SysUserOnline> {
+        self.cache.get(token_id).await
+    }
+
+    pub fn contains(&self, token_id: &str) -> bool {
+        self.cache.contains_key(token_id)
+    }
+
+    pub async fn remove(&self, token_id: &str) -> Option<SysUserOnline> {
+        self.cache.remove(token_id).await
+    }
+
+    /// 吊销会话：移除会话并将整个令牌族加入吊销列表
+    pub async fn revoke(&self, jwt: &JwtAuthUtil, token_id: &str) -> Option<SysUserOnline> {
+        jwt.revoke_session(token_id).await;
+        self.remove(token_id).await
+    }
+
+    /// 所有在线会话，按登录时间倒序
+    pub fn list(&self) -> Vec<SysUserOnline> {
+        let mut sessions: Vec<SysUserOnline> =
+            self.cache.iter().map(|(_, session)| session).collect();
+        sessions.sort_by_key(|s| Reverse(s.login_time));
+        sessions
+    }
+}
@@ -0,0 +1,221 @@
+//! 应用状态
+//!
+//! [`AppState`] 在 `app::run` 中构建，由 [`inject`] 中间件放入每个请求的 `Depot` 和请求扩展，
+//! 处理函数通过 [`Db`]、[`Jwt`]、[`Config`]、[`Ext`] 提取器以参数形式获取，需要多个状态时使用 [`State`]，
+//! 中间件等拿不到提取器的地方使用 [`AppState::from_depot`]。
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    ops::Deref,
+    sync::Arc,
+};
+
+use common::{AppError, AppResult};
+use salvo::{
+    Depot, FlowCtrl, Handler, Request, Response, async_trait,
+    extract::{Extractible, Metadata},
+    oapi::{Components, EndpointArgRegister, Operation},
+};
+use sqlx::PgPool;
+
+use crate::{config::Setting, jwt::JwtAuthUtil};
+
+/// 应用状态，克隆开销很小
+#[derive(Clone)]
+pub struct AppState {
+    db: PgPool,
+    jwt: Arc<JwtAuthUtil>,
+    config: Arc<Setting>,
+    /// 业务模块的状态，按类型区分
+    extensions: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl AppState {
+    pub fn new(db: PgPool, jwt: JwtAuthUtil, config: Setting) -> Self {
+        Self {
+            db,
+            jwt: Arc::new(jwt),
+            config: Arc::new(config),
+            extensions: Arc::default(),
+        }
+    }
+
+    /// 添加业务模块的状态，同一类型只保留最后一次添加的值
+    pub fn with<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        Arc::make_mut(&mut self.extensions).insert(TypeId::of::<T>(), Arc::new(value));
+        self
+    }
+
+    pub fn db(&self) -> &PgPool {
+        &self.db
+    }
+
+    pub fn jwt(&self) -> &JwtAuthUtil {
+        &self.jwt
+    }
+
+    pub fn config(&self) -> &Setting {
+        &self.config
+    }
+
+    /// 获取业务模块的状态
+    pub fn ext<T: Send + Sync + 'static>(&self) -> AppResult<&T> {
+        self.extensions
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .ok_or_else(|| AppError::Other(format!("应用状态缺少 {}", std::any::type_name::<T>())))
+    }
+
+    pub fn from_depot(depot: &Depot) -> AppResult<&AppState> {
+        depot
+            .obtain::<AppState>()
+            .map_err(|_| AppError::Other("应用状态未注入".to_string()))
+    }
+
+    pub(crate) fn from_request(req: &Request) -> AppResult<&AppState> {
+        req.extensions()
+            .get::<AppState>()
+            .ok_or(AppError::Other("应用状态未注入".to_string()))
+    }
+}
+
+/// 注入应用状态的中间件，需挂在根路由上
+pub fn inject(state: AppState) -> StateInjector {
+    StateInjector(state)
+}
+
+pub struct StateInjector(AppState);
+
+#[async_trait]
+impl Handler for StateInjector {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        _res: &mut Response,
+        _ctrl: &mut FlowCtrl,
+    ) {
+        req.extensions_mut().insert(self.0.clone());
+        depot.inject(self.0.clone());
+    }
+}
+
+/// 数据库连接池
+pub struct Db(pub PgPool);
+
+/// JWT 工具
+pub struct Jwt(pub Arc<JwtAuthUtil>);
+
+/// 应用配置
+pub struct Config(pub Arc<Setting>);
+
+/// 完整的应用状态
+pub struct State(pub AppState);
+
+/// 通过 [`AppState::with`] 添加的业务模块状态
+pub struct Ext<T>(pub T);
+
+macro_rules! state_extractor {
+    ($ty:ident, $target:ty, |$state:ident| $value:expr) => {
+        impl Deref for $ty {
+            type Target = $target;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<'ex> Extractible<'ex> for $ty {
+            fn metadata() -> &'static Metadata {
+                static METADATA: Metadata = Metadata::new(stringify!($ty));
+                &METADATA
+            }
+
+            #[allow(refining_impl_trait)]
+            async fn extract(req: &'ex mut Request) -> Result<Self, AppError> {
+                let $state = AppState::from_request(req)?;
+                Ok($ty($value))
+            }
+        }
+
+        // 不出现在 OpenAPI 文档中
+        impl EndpointArgRegister for $ty {
+            fn register(_components: &mut Components, _operation: &mut Operation, _arg: &str) {}
+        }
+    };
+}
+
+state_extractor!(Db, PgPool, |state| state.db.clone());
+state_extractor!(Jwt, JwtAuthUtil, |state| state.jwt.clone());
+state_extractor!(Config, Setting, |state| state.config.clone());
+state_extractor!(State, AppState, |state| state.clone());
+
+impl<T> Deref for Ext<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'ex, T: Clone + Send + Sync + 'static> Extractible<'ex> for Ext<T> {
+    fn metadata() -> &'static Metadata {
+        static METADATA: Metadata = Metadata::new("Ext");
+        &METADATA
+    }
+
+    #[allow(refining_impl_trait)]
+    async fn extract(req: &'ex mut Request) -> Result<Self, AppError> {
+        Ok(Ext(AppState::from_request(req)?.ext::<T>()?.clone()))
+    }
+}
+
+impl<T> EndpointArgRegister for Ext<T> {
+    fn register(_components: &mut Components, _operation: &mut Operation, _arg: &str) {}
+}
+
+#[cfg(test)]
+mod test {
+    use common::AppResult;
+    use config::{Config, File, FileFormat};
+    use sqlx::PgPool;
+
+    use crate::{
+        Setting,
+        jwt::{JwtAuthUtil, JwtConfig},
+        state::AppState,
+    };
+
+    fn state(issuer: &str) -> AppResult<AppState> {
+        let db = PgPool::connect_lazy("postgres://localhost/test")?;
+        let jwt = JwtAuthUtil::new(JwtConfig::new(
+            "secret".to_string(),
+            5,
+            10,
+            issuer.to_string(),
+        ));
+        let setting: Setting = Config::builder()
+            .add_source(File::from_str(
+                include_str!("../../config/test.toml"),
+                FileFormat::Toml,
+            ))
+            .build()?
+            .try_deserialize()?;
+        Ok(AppState::new(db, jwt, setting))
+    }
+
+    #[tokio::test]
+    async fn state_test() -> AppResult<()> {
+        // 同一进程中的两个实例互不影响
+        let a = state("a")?.with(1_u32);
+        let b = state("b")?.with(2_u32).with("b");
+        assert_eq!(a.jwt().issuer(), "a");
+        assert_eq!(b.jwt().issuer(), "b");
+        assert_eq!(*a.ext::<u32>()?, 1);
+        assert_eq!(*b.ext::<u32>()?, 2);
+        assert_eq!(*b.ext::<&str>()?, "b");
+        assert!(a.ext::<&str>().is_err());
+        Ok(())
+    }
+}
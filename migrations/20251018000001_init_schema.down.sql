@@ -0,0 +1,11 @@
+-- 回滚初始表结构（会删除所有业务数据）
+DROP TABLE IF EXISTS sys_upload_files;
+DROP TABLE IF EXISTS sys_login_infor;
+DROP TABLE IF EXISTS sys_oper_log;
+DROP TABLE IF EXISTS sys_dict_data;
+DROP TABLE IF EXISTS sys_dict_type;
+DROP TABLE IF EXISTS sys_role_menu;
+DROP TABLE IF EXISTS sys_user_role;
+DROP TABLE IF EXISTS sys_menu;
+DROP TABLE IF EXISTS sys_role;
+DROP TABLE IF EXISTS sys_user;
@@ -0,0 +1,45 @@
+-- 回滚到旧版 sql/system.sql 的表结构（会删除新增表及字段中的数据）
+DROP INDEX IF EXISTS idx_sys_oper_log_rid;
+ALTER TABLE sys_oper_log DROP COLUMN IF EXISTS request_id;
+ALTER TABLE sys_oper_log DROP COLUMN IF EXISTS oper_nick_name;
+ALTER TABLE sys_oper_log
+    ALTER COLUMN business_type TYPE INT,
+    ALTER COLUMN operator_type TYPE INT,
+    ALTER COLUMN status TYPE INT;
+
+ALTER TABLE sys_dict_data
+    ALTER COLUMN dict_sort DROP NOT NULL,
+    ALTER COLUMN dict_label DROP NOT NULL,
+    ALTER COLUMN dict_value DROP NOT NULL,
+    ALTER COLUMN dict_type DROP NOT NULL,
+    ALTER COLUMN is_default DROP NOT NULL,
+    ALTER COLUMN status DROP NOT NULL,
+    ALTER COLUMN create_by DROP NOT NULL,
+    ALTER COLUMN update_by DROP NOT NULL;
+ALTER TABLE sys_dict_type
+    ALTER COLUMN dict_name DROP NOT NULL,
+    ALTER COLUMN dict_type DROP NOT NULL,
+    ALTER COLUMN status DROP NOT NULL,
+    ALTER COLUMN create_by DROP NOT NULL,
+    ALTER COLUMN update_by DROP NOT NULL;
+
+DROP TABLE IF EXISTS sys_role_dept;
+ALTER TABLE sys_role_menu ALTER COLUMN role_id TYPE BIGINT, ALTER COLUMN menu_id TYPE BIGINT;
+ALTER TABLE sys_user_role ALTER COLUMN user_id TYPE BIGINT, ALTER COLUMN role_id TYPE BIGINT;
+DROP TABLE IF EXISTS sys_user_post;
+DROP TABLE IF EXISTS sys_post;
+DROP TABLE IF EXISTS sys_dept;
+
+ALTER TABLE sys_menu DROP COLUMN IF EXISTS del_flag;
+ALTER TABLE sys_menu DROP COLUMN IF EXISTS perms;
+ALTER TABLE sys_menu ALTER COLUMN parent_id TYPE BIGINT;
+
+ALTER TABLE sys_role DROP COLUMN IF EXISTS force_mfa;
+
+DROP TABLE IF EXISTS sys_api_key;
+DROP TABLE IF EXISTS sys_user_sso;
+DROP TABLE IF EXISTS sys_user_recovery_code;
+DROP TABLE IF EXISTS sys_user_pwd_history;
+ALTER TABLE sys_user DROP COLUMN IF EXISTS dept_id;
+ALTER TABLE sys_user DROP COLUMN IF EXISTS totp_enabled;
+ALTER TABLE sys_user DROP COLUMN IF EXISTS totp_secret;
@@ -0,0 +1,257 @@
+-- 旧版 sql/system.sql 之后新增的表结构：双因素认证、密码策略、单点登录、API Key、部门、岗位、请求编号等
+-- 新增列使用 ADD COLUMN IF NOT EXISTS，手工补过字段的库也可以执行
+
+-- ----------------------------
+-- 用户信息表
+-- ----------------------------
+ALTER TABLE sys_user ADD COLUMN IF NOT EXISTS totp_secret VARCHAR(64);
+ALTER TABLE sys_user ADD COLUMN IF NOT EXISTS totp_enabled CHAR(1) DEFAULT '0';
+ALTER TABLE sys_user ADD COLUMN IF NOT EXISTS dept_id INT;
+
+COMMENT ON COLUMN sys_user.totp_secret IS 'TOTP 密钥（Base32，绑定确认前为待确认密钥）';
+COMMENT ON COLUMN sys_user.totp_enabled IS '双因素认证状态（0未启用 1已启用）';
+COMMENT ON COLUMN sys_user.dept_id IS '所属部门ID';
+
+
+-- 用户历史密码表（用于禁止重复使用最近的密码）
+CREATE TABLE IF NOT EXISTS sys_user_pwd_history (
+    history_id        SERIAL PRIMARY KEY,
+    user_id           INT NOT NULL,
+    password          VARCHAR(100) NOT NULL,
+    create_time       TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_pwd_history_user ON sys_user_pwd_history (user_id, create_time);
+
+COMMENT ON TABLE sys_user_pwd_history IS '用户历史密码表';
+COMMENT ON COLUMN sys_user_pwd_history.history_id IS '记录ID';
+COMMENT ON COLUMN sys_user_pwd_history.user_id IS '用户ID';
+COMMENT ON COLUMN sys_user_pwd_history.password IS '密码哈希（Argon2）';
+COMMENT ON COLUMN sys_user_pwd_history.create_time IS '设置时间';
+
+
+-- 用户恢复码表（双因素认证备用码，Argon2 哈希存储）
+CREATE TABLE IF NOT EXISTS sys_user_recovery_code (
+    code_id           SERIAL PRIMARY KEY,
+    user_id           INT NOT NULL,
+    code_hash         VARCHAR(128) NOT NULL,
+    used_time         TIMESTAMPTZ,
+    create_time       TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_recovery_code_user ON sys_user_recovery_code (user_id);
+
+COMMENT ON TABLE sys_user_recovery_code IS '用户恢复码表';
+COMMENT ON COLUMN sys_user_recovery_code.code_id IS '恢复码ID';
+COMMENT ON COLUMN sys_user_recovery_code.user_id IS '用户ID';
+COMMENT ON COLUMN sys_user_recovery_code.code_hash IS '恢复码哈希（Argon2）';
+COMMENT ON COLUMN sys_user_recovery_code.used_time IS '使用时间，为空表示未使用';
+COMMENT ON COLUMN sys_user_recovery_code.create_time IS '创建时间';
+
+
+-- 第三方账号绑定表（OIDC 单点登录外部账号与系统用户的对应关系）
+CREATE TABLE IF NOT EXISTS sys_user_sso (
+    sso_id            SERIAL PRIMARY KEY,
+    provider          VARCHAR(64) NOT NULL,
+    subject           VARCHAR(255) NOT NULL,
+    user_id           INT NOT NULL,
+    email             VARCHAR(255),
+    create_time       TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, subject),
+    UNIQUE (provider, user_id)
+);
+
+COMMENT ON TABLE sys_user_sso IS '第三方账号绑定表';
+COMMENT ON COLUMN sys_user_sso.sso_id IS '绑定ID';
+COMMENT ON COLUMN sys_user_sso.provider IS '提供方名称';
+COMMENT ON COLUMN sys_user_sso.subject IS '外部账号标识（ID Token sub）';
+COMMENT ON COLUMN sys_user_sso.user_id IS '用户ID';
+COMMENT ON COLUMN sys_user_sso.email IS '外部账号邮箱';
+COMMENT ON COLUMN sys_user_sso.create_time IS '绑定时间';
+
+
+-- API Key 表（个人访问令牌，只保存 SHA-256 摘要）
+CREATE TABLE IF NOT EXISTS sys_api_key (
+    key_id            SERIAL PRIMARY KEY,
+    user_id           INT NOT NULL,
+    key_name          VARCHAR(64) NOT NULL,
+    key_prefix        VARCHAR(16) NOT NULL UNIQUE,
+    key_hash          VARCHAR(64) NOT NULL,
+    scopes            TEXT[] NOT NULL DEFAULT '{}',
+    expire_time       TIMESTAMPTZ,
+    last_used_time    TIMESTAMPTZ,
+    revoked           CHAR(1) NOT NULL DEFAULT '0',
+    create_time       TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_api_key_user ON sys_api_key (user_id);
+
+COMMENT ON TABLE sys_api_key IS 'API Key 表';
+COMMENT ON COLUMN sys_api_key.key_id IS 'API Key ID';
+COMMENT ON COLUMN sys_api_key.user_id IS '所属用户ID';
+COMMENT ON COLUMN sys_api_key.key_name IS '名称';
+COMMENT ON COLUMN sys_api_key.key_prefix IS '令牌前缀（用于查找和展示）';
+COMMENT ON COLUMN sys_api_key.key_hash IS '令牌摘要（SHA-256）';
+COMMENT ON COLUMN sys_api_key.scopes IS '允许使用的权限标识';
+COMMENT ON COLUMN sys_api_key.expire_time IS '过期时间，为空表示永不过期';
+COMMENT ON COLUMN sys_api_key.last_used_time IS '最近使用时间';
+COMMENT ON COLUMN sys_api_key.revoked IS '吊销标志（0正常 1已吊销）';
+COMMENT ON COLUMN sys_api_key.create_time IS '创建时间';
+
+
+-- ----------------------------
+-- 角色信息表
+-- ----------------------------
+ALTER TABLE sys_role ADD COLUMN IF NOT EXISTS force_mfa CHAR(1) DEFAULT '0';
+
+COMMENT ON COLUMN sys_role.force_mfa IS '强制双因素认证（0否 1是）';
+
+
+-- ----------------------------
+-- 菜单权限表
+-- ----------------------------
+ALTER TABLE sys_menu ALTER COLUMN parent_id TYPE INT;
+ALTER TABLE sys_menu ADD COLUMN IF NOT EXISTS perms VARCHAR(100) DEFAULT '';
+ALTER TABLE sys_menu ADD COLUMN IF NOT EXISTS del_flag CHAR(1) DEFAULT '0';
+
+COMMENT ON COLUMN sys_menu.perms IS '权限标识';
+COMMENT ON COLUMN sys_menu.del_flag IS '删除标志（0代表存在 2代表删除）';
+
+
+-- ----------------------------
+-- 部门表（ancestors 保存祖级列表，用于查询子孙部门）
+-- ----------------------------
+CREATE TABLE IF NOT EXISTS sys_dept (
+    dept_id           SERIAL PRIMARY KEY,
+    parent_id         INT NOT NULL DEFAULT 0,
+    ancestors         VARCHAR(500) NOT NULL DEFAULT '',
+    dept_name         VARCHAR(30) NOT NULL,
+    order_num         INT NOT NULL DEFAULT 0,
+    leader            VARCHAR(20),
+    phone             VARCHAR(11),
+    email             VARCHAR(50),
+    status            CHAR(1) NOT NULL DEFAULT '0',
+    del_flag          CHAR(1) NOT NULL DEFAULT '0',
+    create_by         VARCHAR(64) DEFAULT '',
+    create_time       TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    update_by         VARCHAR(64) DEFAULT '',
+    update_time       TIMESTAMPTZ
+);
+CREATE INDEX IF NOT EXISTS idx_dept_parent ON sys_dept (parent_id);
+
+COMMENT ON TABLE sys_dept IS '部门表';
+COMMENT ON COLUMN sys_dept.dept_id IS '部门ID';
+COMMENT ON COLUMN sys_dept.parent_id IS '父部门ID（0表示根部门）';
+COMMENT ON COLUMN sys_dept.ancestors IS '祖级列表（逗号分隔，如 0,1,3）';
+COMMENT ON COLUMN sys_dept.dept_name IS '部门名称';
+COMMENT ON COLUMN sys_dept.order_num IS '显示顺序';
+COMMENT ON COLUMN sys_dept.leader IS '负责人';
+COMMENT ON COLUMN sys_dept.phone IS '联系电话';
+COMMENT ON COLUMN sys_dept.email IS '邮箱';
+COMMENT ON COLUMN sys_dept.status IS '部门状态（0正常 1停用）';
+COMMENT ON COLUMN sys_dept.del_flag IS '删除标志（0代表存在 2代表删除）';
+COMMENT ON COLUMN sys_dept.create_by IS '创建者';
+COMMENT ON COLUMN sys_dept.create_time IS '创建时间';
+COMMENT ON COLUMN sys_dept.update_by IS '更新者';
+COMMENT ON COLUMN sys_dept.update_time IS '更新时间';
+
+
+-- ----------------------------
+-- 岗位信息表
+-- ----------------------------
+CREATE TABLE IF NOT EXISTS sys_post (
+    post_id           SERIAL PRIMARY KEY,
+    post_code         VARCHAR(64) NOT NULL UNIQUE,
+    post_name         VARCHAR(50) NOT NULL,
+    post_sort         INT NOT NULL DEFAULT 0,
+    status            CHAR(1) NOT NULL DEFAULT '0',
+    create_by         VARCHAR(64) DEFAULT '',
+    create_time       TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    update_by         VARCHAR(64) DEFAULT '',
+    update_time       TIMESTAMPTZ,
+    remark            VARCHAR(500)
+);
+
+COMMENT ON TABLE sys_post IS '岗位信息表';
+COMMENT ON COLUMN sys_post.post_id IS '岗位ID';
+COMMENT ON COLUMN sys_post.post_code IS '岗位编码';
+COMMENT ON COLUMN sys_post.post_name IS '岗位名称';
+COMMENT ON COLUMN sys_post.post_sort IS '显示顺序';
+COMMENT ON COLUMN sys_post.status IS '状态（0正常 1停用）';
+COMMENT ON COLUMN sys_post.create_by IS '创建者';
+COMMENT ON COLUMN sys_post.create_time IS '创建时间';
+COMMENT ON COLUMN sys_post.update_by IS '更新者';
+COMMENT ON COLUMN sys_post.update_time IS '更新时间';
+COMMENT ON COLUMN sys_post.remark IS '备注';
+
+
+-- 用户与岗位关联表
+CREATE TABLE IF NOT EXISTS sys_user_post (
+    user_id   INT NOT NULL,
+    post_id   INT NOT NULL,
+    PRIMARY KEY (user_id, post_id)
+);
+CREATE INDEX IF NOT EXISTS idx_user_post_post ON sys_user_post (post_id);
+
+COMMENT ON TABLE sys_user_post IS '用户与岗位关联表';
+COMMENT ON COLUMN sys_user_post.user_id IS '用户ID';
+COMMENT ON COLUMN sys_user_post.post_id IS '岗位ID';
+
+
+-- 用户和角色关联表、角色和菜单关联表
+ALTER TABLE sys_user_role ALTER COLUMN user_id TYPE INT, ALTER COLUMN role_id TYPE INT;
+ALTER TABLE sys_role_menu ALTER COLUMN role_id TYPE INT, ALTER COLUMN menu_id TYPE INT;
+
+
+-- 角色和部门关联表（自定义数据权限）
+CREATE TABLE IF NOT EXISTS sys_role_dept (
+    role_id   INT NOT NULL,
+    dept_id   INT NOT NULL,
+    PRIMARY KEY (role_id, dept_id)
+);
+
+COMMENT ON TABLE sys_role_dept IS '角色和部门关联表';
+COMMENT ON COLUMN sys_role_dept.role_id IS '角色ID';
+COMMENT ON COLUMN sys_role_dept.dept_id IS '部门ID';
+
+
+-- ----------------------------
+-- 字典类型表、字典数据表
+-- ----------------------------
+UPDATE sys_dict_type SET dict_name = COALESCE(dict_name, ''), dict_type = COALESCE(dict_type, ''),
+    status = COALESCE(status, '0'), create_by = COALESCE(create_by, ''), update_by = COALESCE(update_by, '')
+WHERE dict_name IS NULL OR dict_type IS NULL OR status IS NULL OR create_by IS NULL OR update_by IS NULL;
+ALTER TABLE sys_dict_type
+    ALTER COLUMN dict_name SET NOT NULL,
+    ALTER COLUMN dict_type SET NOT NULL,
+    ALTER COLUMN status SET NOT NULL,
+    ALTER COLUMN create_by SET NOT NULL,
+    ALTER COLUMN update_by SET NOT NULL;
+
+UPDATE sys_dict_data SET dict_sort = COALESCE(dict_sort, 0), dict_label = COALESCE(dict_label, ''),
+    dict_value = COALESCE(dict_value, ''), dict_type = COALESCE(dict_type, ''),
+    is_default = COALESCE(is_default, 'N'), status = COALESCE(status, '0'),
+    create_by = COALESCE(create_by, ''), update_by = COALESCE(update_by, '')
+WHERE dict_sort IS NULL OR dict_label IS NULL OR dict_value IS NULL OR dict_type IS NULL
+    OR is_default IS NULL OR status IS NULL OR create_by IS NULL OR update_by IS NULL;
+ALTER TABLE sys_dict_data
+    ALTER COLUMN dict_sort SET NOT NULL,
+    ALTER COLUMN dict_label SET NOT NULL,
+    ALTER COLUMN dict_value SET NOT NULL,
+    ALTER COLUMN dict_type SET NOT NULL,
+    ALTER COLUMN is_default SET NOT NULL,
+    ALTER COLUMN status SET NOT NULL,
+    ALTER COLUMN create_by SET NOT NULL,
+    ALTER COLUMN update_by SET NOT NULL;
+
+
+-- ----------------------------
+-- 操作日志记录
+-- ----------------------------
+ALTER TABLE sys_oper_log
+    ALTER COLUMN business_type TYPE SMALLINT,
+    ALTER COLUMN operator_type TYPE SMALLINT,
+    ALTER COLUMN status TYPE SMALLINT;
+ALTER TABLE sys_oper_log ADD COLUMN IF NOT EXISTS oper_nick_name VARCHAR(50) DEFAULT '';
+ALTER TABLE sys_oper_log ADD COLUMN IF NOT EXISTS request_id VARCHAR(64) DEFAULT '';
+CREATE INDEX IF NOT EXISTS idx_sys_oper_log_rid ON sys_oper_log (request_id);
+
+COMMENT ON COLUMN sys_oper_log.oper_nick_name IS '操作人员昵称';
+COMMENT ON COLUMN sys_oper_log.request_id IS '请求编号';
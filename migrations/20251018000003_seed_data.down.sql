@@ -0,0 +1,3 @@
+-- 回滚初始数据
+DELETE FROM sys_dept WHERE dept_id = 1;
+DELETE FROM sys_role WHERE role_id IN (1, 2);
@@ -0,0 +1,14 @@
+-- 初始数据：内置角色、根部门
+-- 管理员账号及其密码哈希因环境而异，不在此写入
+
+INSERT INTO sys_role (role_id, role_name, role_key, role_sort, data_scope, status, create_by, remark)
+VALUES
+    (1, '超级管理员', 'admin',  1, '1', '0', 'admin', '超级管理员'),
+    (2, '普通角色',   'common', 2, '2', '0', 'admin', '普通角色')
+ON CONFLICT (role_id) DO NOTHING;
+SELECT setval('sys_role_role_id_seq', GREATEST((SELECT MAX(role_id) FROM sys_role), 1));
+
+INSERT INTO sys_dept (dept_id, parent_id, ancestors, dept_name, order_num, create_by)
+VALUES (1, 0, '0', '总公司', 0, 'admin')
+ON CONFLICT (dept_id) DO NOTHING;
+SELECT setval('sys_dept_dept_id_seq', GREATEST((SELECT MAX(dept_id) FROM sys_dept), 1));
@@ -0,0 +1,2 @@
+-- 回滚 TOTP 时间步记录
+ALTER TABLE sys_user DROP COLUMN IF EXISTS totp_last_step;
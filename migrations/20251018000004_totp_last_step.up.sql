@@ -0,0 +1,4 @@
+-- 记录最后一次通过校验的 TOTP 时间步，同一动态口令不能重复使用
+ALTER TABLE sys_user ADD COLUMN IF NOT EXISTS totp_last_step BIGINT;
+
+COMMENT ON COLUMN sys_user.totp_last_step IS '最后一次使用的 TOTP 时间步（防重放）';
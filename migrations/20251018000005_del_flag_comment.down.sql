@@ -0,0 +1,3 @@
+-- 回滚删除标志注释
+COMMENT ON COLUMN sys_user.del_flag IS '删除标志（0代表存在 2代表删除）';
+COMMENT ON COLUMN sys_dept.del_flag IS '删除标志（0代表存在 2代表删除）';
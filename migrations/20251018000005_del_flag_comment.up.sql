@@ -0,0 +1,3 @@
+-- 软删除统一使用 del_flag = '1'
+COMMENT ON COLUMN sys_user.del_flag IS '删除标志（0代表存在 1代表删除）';
+COMMENT ON COLUMN sys_dept.del_flag IS '删除标志（0代表存在 1代表删除）';
@@ -1,10 +1,14 @@
 use salvo::Router;
 
+pub mod log_level;
 pub mod login_info;
+pub mod online;
 pub mod operlog;
 pub fn init_router() -> Router {
     Router::new()
         .path("monitor")
         .push(operlog::router::init_router())
         .push(login_info::router::init_router())
+        .push(online::router::init_router())
+        .push(log_level::router::init_router())
 }
@@ -0,0 +1,25 @@
+use common::{AppResult, response::ResponseResult};
+use framework::{log::LogTool, state::Ext};
+use salvo::Writer;
+use salvo::oapi::{endpoint, extract::JsonBody};
+use tracing::info;
+
+use crate::log_level::model::LogLevel;
+
+#[endpoint(tags("日志级别"), summary = "查询日志级别")]
+pub(crate) async fn get_level(log: Ext<LogTool>) -> AppResult<ResponseResult<LogLevel>> {
+    info!("[HANDLER] Entering log_level::get_level");
+    let level = log.level()?;
+    Ok(ResponseResult::success(LogLevel { level }))
+}
+
+#[endpoint(tags("日志级别"), summary = "修改日志级别")]
+pub(crate) async fn set_level(
+    body: JsonBody<LogLevel>,
+    log: Ext<LogTool>,
+) -> AppResult<ResponseResult<()>> {
+    let body = body.into_inner();
+    info!("[HANDLER] Entering log_level::set_level:{:?}", body);
+    log.set_level(&body.level)?;
+    ResponseResult::success_msg("修改成功").into()
+}
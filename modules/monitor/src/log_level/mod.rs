@@ -0,0 +1,3 @@
+pub mod handle;
+pub mod model;
+pub mod router;
@@ -0,0 +1,10 @@
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+
+/// 日志级别
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLevel {
+    /// 日志级别或过滤指令，如 `info`、`info,sqlx=debug`
+    pub level: String,
+}
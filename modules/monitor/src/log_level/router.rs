@@ -0,0 +1,20 @@
+use common::constants::Permission;
+use framework::midddleware::require_perm;
+use salvo::Router;
+
+use crate::log_level::handle;
+
+pub fn init_router() -> Router {
+    Router::new()
+        .path("log/level")
+        .push(
+            Router::new()
+                .hoop(require_perm(Permission::LogLevelQuery))
+                .get(handle::get_level),
+        )
+        .push(
+            Router::new()
+                .hoop(require_perm(Permission::LogLevelEdit))
+                .put(handle::set_level),
+        )
+}
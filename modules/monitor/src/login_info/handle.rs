@@ -1,9 +1,15 @@
 use common::{
     AppResult, page_reponse::PageReponse, page_reqest::PageRequest, response::ResponseResult,
 };
-use framework::db::DBPool;
+use framework::{
+    login_lock::LoginLockTool,
+    state::{Db, Ext},
+};
 use salvo::Writer;
-use salvo::oapi::{endpoint, extract::JsonBody};
+use salvo::oapi::{
+    endpoint,
+    extract::{JsonBody, PathParam},
+};
 use tracing::info;
 
 use crate::login_info::{
@@ -14,10 +20,32 @@ use crate::login_info::{
 #[endpoint(tags("登录日志"), summary = "分页")]
 pub(crate) async fn page(
     query: JsonBody<PageRequest<ListLogininforQuery>>,
+    db: Db,
 ) -> AppResult<ResponseResult<PageReponse<SysLoginInfor>>> {
     let query = query.into_inner();
     info!("[HANDLER] Entering operlog::page:{:?}", query);
-    let db = DBPool::get().await?;
-    let page_result = service::select_logininfor_list(db, query).await?;
+    let page_result = service::select_logininfor_list(&db, query).await?;
     Ok(ResponseResult::success(page_result))
 }
+
+#[endpoint(tags("登录日志"), summary = "账户解锁")]
+pub(crate) async fn unlock(
+    user_name: PathParam<String>,
+    login_lock: Ext<LoginLockTool>,
+) -> AppResult<ResponseResult<()>> {
+    let user_name = user_name.into_inner();
+    info!("[HANDLER] Entering login_info::unlock:{}", user_name);
+    service::unlock(&login_lock, &user_name).await?;
+    ResponseResult::success_msg("解锁成功").into()
+}
+
+#[endpoint(tags("登录日志"), summary = "IP解锁")]
+pub(crate) async fn unlock_ip(
+    ipaddr: PathParam<String>,
+    login_lock: Ext<LoginLockTool>,
+) -> AppResult<ResponseResult<()>> {
+    let ipaddr = ipaddr.into_inner();
+    info!("[HANDLER] Entering login_info::unlock_ip:{}", ipaddr);
+    service::unlock_ip(&login_lock, &ipaddr).await?;
+    ResponseResult::success_msg("解锁成功").into()
+}
@@ -1,3 +1,5 @@
+use common::constants::Permission;
+use framework::midddleware::require_perm;
 use salvo::Router;
 
 use crate::login_info::handle;
@@ -5,5 +7,19 @@ use crate::login_info::handle;
 pub fn init_router() -> Router {
     Router::new()
         .path("login_info")
-        .push(Router::with_path("page").get(handle::page))
+        .push(
+            Router::with_path("page")
+                .hoop(require_perm(Permission::LogininforList))
+                .get(handle::page),
+        )
+        .push(
+            Router::with_path("unlock/{user_name}")
+                .hoop(require_perm(Permission::LogininforUnlock))
+                .get(handle::unlock),
+        )
+        .push(
+            Router::with_path("unlock_ip/{ipaddr}")
+                .hoop(require_perm(Permission::LogininforUnlock))
+                .get(handle::unlock_ip),
+        )
 }
@@ -4,6 +4,7 @@ use super::model::SysLoginInfor;
 use common::{
     AppResult, SqlBuilder, error::AppError, page_reponse::PageReponse, page_reqest::PageRequest,
 };
+use framework::login_lock::LoginLockTool;
 use sqlx::PgPool;
 use tracing::info;
 
@@ -34,7 +35,7 @@ pub async fn add_logininfor(db: &PgPool, log: SysLoginInfor) -> Result<(), AppEr
 
 /// 查询登录日志列表（分页）
 pub async fn select_logininfor_list(
-    db: &'static PgPool,
+    db: &PgPool,
     params: PageRequest<ListLogininforQuery>,
 ) -> AppResult<PageReponse<SysLoginInfor>> {
     info!(
@@ -52,6 +53,24 @@ pub async fn select_logininfor_list(
     Ok(login_info_page)
 }
 
+/// 解除用户登录锁定
+pub async fn unlock(login_lock: &LoginLockTool, user_name: &str) -> AppResult<()> {
+    info!("[SERVICE] Entering unlock with user_name: {}", user_name);
+    if login_lock.unlock(user_name).await {
+        info!("[SERVICE] User '{}' has been unlocked.", user_name);
+    }
+    Ok(())
+}
+
+/// 解除IP登录锁定
+pub async fn unlock_ip(login_lock: &LoginLockTool, ipaddr: &str) -> AppResult<()> {
+    info!("[SERVICE] Entering unlock_ip with ipaddr: {}", ipaddr);
+    if login_lock.unlock_ip(ipaddr).await {
+        info!("[SERVICE] IP '{}' has been unlocked.", ipaddr);
+    }
+    Ok(())
+}
+
 // /// 批量删除登录日志
 // pub async fn delete_logininfor_by_ids(db: &MySqlPool, info_ids: &[i64]) -> Result<u64, AppError> {
 //     info!(
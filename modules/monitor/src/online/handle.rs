@@ -0,0 +1,42 @@
+use common::{
+    AppResult, models::sys_user_online::SysUserOnline, page_reponse::PageReponse,
+    page_reqest::PageRequest, response::ResponseResult,
+};
+use framework::{
+    session::SessionCache,
+    state::{Ext, Jwt},
+};
+use salvo::oapi::{
+    endpoint,
+    extract::{JsonBody, PathParam},
+};
+use salvo::{Depot, Writer};
+use tracing::info;
+
+use crate::online::{model::ListOnlineQuery, service};
+use crate::operlog::model::{BusinessType, LogMeta};
+
+#[endpoint(tags("在线用户"), summary = "分页")]
+pub(crate) async fn page(
+    query: JsonBody<PageRequest<ListOnlineQuery>>,
+    sessions: Ext<SessionCache>,
+) -> AppResult<ResponseResult<PageReponse<SysUserOnline>>> {
+    let query = query.into_inner();
+    info!("[HANDLER] Entering online::page:{:?}", query);
+    let page_result = service::page(&sessions, query)?;
+    Ok(ResponseResult::success(page_result))
+}
+
+#[endpoint(tags("在线用户"), summary = "强退")]
+pub(crate) async fn force_logout(
+    token_id: PathParam<String>,
+    depot: &mut Depot,
+    jwt: Jwt,
+    sessions: Ext<SessionCache>,
+) -> AppResult<ResponseResult<()>> {
+    let token_id = token_id.into_inner();
+    info!("[HANDLER] Entering online::force_logout:{}", token_id);
+    LogMeta::set(depot, "在线用户", BusinessType::Other.get_value(), "强退");
+    service::force_logout(&jwt, &sessions, &token_id).await?;
+    ResponseResult::success_msg("强退成功").into()
+}
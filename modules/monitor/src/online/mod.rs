@@ -0,0 +1,4 @@
+pub mod handle;
+pub mod model;
+pub mod router;
+pub mod service;
@@ -0,0 +1,13 @@
+use salvo::oapi::ToSchema;
+use serde::Deserialize;
+
+/// 用于在线用户列表查询的参数结构体
+#[derive(Deserialize, Debug, ToSchema, Default)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct ListOnlineQuery {
+    /// 登录IP地址
+    pub ipaddr: Option<String>,
+    /// 用户名称
+    pub user_name: Option<String>,
+}
@@ -0,0 +1,20 @@
+use common::constants::Permission;
+use framework::midddleware::require_perm;
+use salvo::Router;
+
+use crate::online::handle;
+
+pub fn init_router() -> Router {
+    Router::new()
+        .path("online")
+        .push(
+            Router::with_path("page")
+                .hoop(require_perm(Permission::OnlineList))
+                .post(handle::page),
+        )
+        .push(
+            Router::with_path("{token_id}")
+                .hoop(require_perm(Permission::OnlineForceLogout))
+                .delete(handle::force_logout),
+        )
+}
@@ -0,0 +1,67 @@
+use common::{
+    AppError, AppResult, models::sys_user_online::SysUserOnline, page_reponse::PageReponse,
+    page_reqest::PageRequest,
+};
+use framework::{jwt::JwtAuthUtil, session::SessionCache};
+use tracing::info;
+
+use crate::online::model::ListOnlineQuery;
+
+/// 在线用户列表（分页），数据来自内存中的会话缓存
+pub(crate) fn page(
+    sessions: &SessionCache,
+    mut params: PageRequest<ListOnlineQuery>,
+) -> AppResult<PageReponse<SysUserOnline>> {
+    info!("[SERVICE] Entering online::page with query: {:?}", params);
+    params.normalize();
+    let ListOnlineQuery { ipaddr, user_name } = &params.query;
+    let sessions: Vec<SysUserOnline> = sessions
+        .list()
+        .into_iter()
+        .filter(|s| {
+            ipaddr.as_deref().is_none_or(|ip| {
+                s.ipaddr
+                    .as_deref()
+                    .is_some_and(|s_ip| s_ip.contains(ip.trim()))
+            })
+        })
+        .filter(|s| {
+            user_name
+                .as_deref()
+                .is_none_or(|name| s.user_name.contains(name.trim()))
+        })
+        .collect();
+    let total = sessions.len() as u32;
+    let items = sessions
+        .into_iter()
+        .skip(params.offset() as usize)
+        .take(params.page_size as usize)
+        .collect();
+    Ok(PageReponse::new(
+        items,
+        params.page,
+        params.page_size,
+        total,
+    ))
+}
+
+/// 强退：吊销会话，该会话签发的令牌随即失效
+pub(crate) async fn force_logout(
+    jwt: &JwtAuthUtil,
+    sessions: &SessionCache,
+    token_id: &str,
+) -> AppResult<()> {
+    info!(
+        "[SERVICE] Entering online::force_logout with token_id: {}",
+        token_id
+    );
+    let session = sessions
+        .revoke(jwt, token_id)
+        .await
+        .ok_or(AppError::RecordNotFound)?;
+    info!(
+        "[SERVICE] Session of user '{}' has been force logged out.",
+        session.user_name
+    );
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use common::utils::time::opt_ts_ms;
-use salvo::oapi::ToSchema;
+use salvo::{Depot, oapi::ToSchema};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
@@ -37,6 +37,8 @@ pub struct SysOperLog {
     pub oper_time: Option<OffsetDateTime>,
     //消耗时间
     pub cost_time: Option<i64>,
+    //请求编号
+    pub request_id: Option<String>,
 }
 
 /// 用于操作日志列表查询的参数结构体
@@ -54,6 +56,8 @@ pub struct ListOperLogQuery {
     pub business_type: Option<i32>,
     /// 操作状态（0正常 1异常）
     pub status: Option<i32>,
+    /// 请求编号
+    pub request_id: Option<String>,
     /// 日期范围查询
     #[serde(with = "opt_ts_ms")]
     pub start_time: Option<OffsetDateTime>,
@@ -93,6 +97,8 @@ pub struct OperLogVO {
     ///操作时间
     #[serde(with = "opt_ts_ms")]
     pub oper_time: Option<OffsetDateTime>,
+    ///请求编号
+    pub request_id: Option<String>,
 }
 
 #[derive(sqlx::FromRow, Debug, Serialize, Deserialize, Default)]
@@ -130,6 +136,8 @@ pub struct OperLogDTO {
     pub oper_time: Option<OffsetDateTime>,
     //消耗时间
     pub cost_time: Option<i64>,
+    //请求编号
+    pub request_id: Option<String>,
 }
 impl From<SysOperLog> for OperLogVO {
     fn from(value: SysOperLog) -> Self {
@@ -148,6 +156,7 @@ impl From<SysOperLog> for OperLogVO {
             error_msg,
             oper_time,
             operator_type,
+            request_id,
             ..
         } = value;
         Self {
@@ -165,6 +174,7 @@ impl From<SysOperLog> for OperLogVO {
             status,
             error_msg,
             oper_time,
+            request_id,
         }
     }
 }
@@ -185,3 +195,33 @@ impl BusinessType {
         }
     }
 }
+
+// 日志元数据，存放在 Depot 中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogMeta {
+    pub title: String,       // 模块标题，如 "文章管理"
+    pub business_type: i16,  // 1:新增, 2:修改, 3:删除, etc.
+    pub method_name: String, // 方法名称
+}
+
+const LOG_META_KEY: &str = "sys_oper_log_meta";
+
+impl LogMeta {
+    // 辅助函数：在 Handler 中调用此方法设置日志信息
+    pub fn set(depot: &mut Depot, title: &str, business_type: i16, method_name: &str) {
+        depot.insert(
+            LOG_META_KEY,
+            LogMeta {
+                title: title.to_string(),
+                business_type,
+                method_name: method_name.to_string(),
+            },
+        );
+    }
+
+    // 辅助函数：在 Middleware 中取出日志信息
+    pub fn get(depot: &Depot) -> Option<&LogMeta> {
+        // 使用 .ok() 将 Result 转换为 Option
+        depot.get::<LogMeta>(LOG_META_KEY).ok()
+    }
+}
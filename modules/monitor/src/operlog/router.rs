@@ -1,9 +1,13 @@
+use common::constants::Permission;
+use framework::midddleware::require_perm;
 use salvo::Router;
 
 use crate::operlog::handle;
 
 pub fn init_router() -> Router {
-    Router::new()
-        .path("operlog")
-        .push(Router::with_path("page").post(handle::page))
+    Router::new().path("operlog").push(
+        Router::with_path("page")
+            .hoop(require_perm(Permission::OperlogList))
+            .post(handle::page),
+    )
 }
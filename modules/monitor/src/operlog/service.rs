@@ -5,7 +5,7 @@ use tracing::info;
 use crate::operlog::model::{ListOperLogQuery, OperLogDTO, OperLogVO, SysOperLog};
 
 pub(crate) async fn page(
-    db: &'static PgPool,
+    db: &PgPool,
     params: PageRequest<ListOperLogQuery>,
 ) -> AppResult<PageReponse<OperLogVO>> {
     info!("[SERVICE] Entering operlog::page with query: {:?}", params);
@@ -19,6 +19,7 @@ pub(crate) async fn page(
         .where_le("oper_time", params.query.end_time)
         .where_eq("status", params.query.status)
         .where_eq("business_type", params.query.business_type)
+        .where_eq("request_id", params.query.request_id)
         .order_by("oper_time", Some("desc"))
         .paginate(params.page, params.page_size);
 
@@ -35,7 +36,7 @@ pub(crate) async fn page(
 pub async fn add(db: &PgPool, log: OperLogDTO) -> AppResult<()> {
     info!("[SERVICE] Entering add operlog with data: {:?}", log);
     sqlx::query!(
-            "INSERT INTO sys_oper_log (title, business_type, method, request_method,operator_type, oper_name, oper_nick_name,oper_url, oper_ip,oper_location, oper_param, json_result, status,error_msg, oper_time, cost_time) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,$14,NOW(), $15)",
+            "INSERT INTO sys_oper_log (title, business_type, method, request_method,operator_type, oper_name, oper_nick_name,oper_url, oper_ip,oper_location, oper_param, json_result, status,error_msg, oper_time, cost_time, request_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,$14,COALESCE($17, NOW()), $15, $16)",
             log.title,
             log.business_type,
             log.method,
@@ -50,7 +51,9 @@ pub async fn add(db: &PgPool, log: OperLogDTO) -> AppResult<()> {
             log.json_result,
             log.status,
             log.error_msg,
-            log.cost_time
+            log.cost_time,
+            log.request_id,
+            log.oper_time
         ).execute(db).await?;
     Ok(())
 }
@@ -0,0 +1,60 @@
+use common::{AppResult, response::ResponseResult};
+use framework::{
+    api_key::ApiKeyTool,
+    permission::PermTool,
+    state::{Db, Ext},
+};
+use salvo::oapi::endpoint;
+use salvo::oapi::extract::{JsonBody, PathParam};
+use salvo::{Depot, Writer};
+use tracing::info;
+
+use crate::api_key::{
+    model::{ApiKeyAddDTO, ApiKeyCreatedVO, ApiKeyVO},
+    service,
+};
+use crate::handle::current_user_id;
+
+/// 创建 API Key，完整令牌只返回一次
+#[endpoint(tags("API Key"), summary = "创建 API Key")]
+pub async fn add(
+    dto: JsonBody<ApiKeyAddDTO>,
+    depot: &mut Depot,
+    db: Db,
+    perm_tool: Ext<PermTool>,
+) -> AppResult<ResponseResult<ApiKeyCreatedVO>> {
+    let user_id = current_user_id(depot)?;
+    info!("[HANDLER] Entering api_key::add with user_id: {}", user_id);
+    let created = service::add(&db, &perm_tool, user_id, dto.into_inner()).await?;
+    Ok(ResponseResult::success_with_msg(
+        "创建成功，请妥善保存令牌，关闭后将无法再次查看",
+        created,
+    ))
+}
+
+/// 查看本人的 API Key 列表
+#[endpoint(tags("API Key"), summary = "API Key 列表")]
+pub async fn list(depot: &mut Depot, db: Db) -> AppResult<ResponseResult<Vec<ApiKeyVO>>> {
+    let user_id = current_user_id(depot)?;
+    info!("[HANDLER] Entering api_key::list with user_id: {}", user_id);
+    ResponseResult::success(service::list(&db, user_id).await?).into()
+}
+
+/// 吊销本人的 API Key，立即失效
+#[endpoint(tags("API Key"), summary = "吊销 API Key")]
+pub async fn revoke(
+    key_id: PathParam<i32>,
+    depot: &mut Depot,
+    db: Db,
+    api_key_tool: Ext<ApiKeyTool>,
+) -> AppResult<ResponseResult<()>> {
+    let key_id = key_id.into_inner();
+    let user_id = current_user_id(depot)?;
+    info!(
+        "[HANDLER] Entering api_key::revoke with user_id: {}, key_id: {}",
+        user_id, key_id
+    );
+    let prefix = service::revoke(&db, user_id, key_id).await?;
+    api_key_tool.invalidate(&prefix).await;
+    ResponseResult::success_msg("吊销成功").into()
+}
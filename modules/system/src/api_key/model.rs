@@ -0,0 +1,40 @@
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// 创建 API Key
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyAddDTO {
+    pub key_name: String,
+    /// 允许使用的权限标识，只能从本人拥有的权限中选择
+    pub scopes: Vec<String>,
+    /// 有效天数，为空表示永不过期
+    pub expire_days: Option<u32>,
+}
+
+/// API Key 列表项（不含令牌本身）
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyVO {
+    pub key_id: i32,
+    pub key_name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub expire_time: Option<OffsetDateTime>,
+    pub last_used_time: Option<OffsetDateTime>,
+    /// 吊销标志（0正常 1已吊销）
+    pub revoked: String,
+    pub create_time: OffsetDateTime,
+}
+
+/// 创建成功：完整令牌只在此时返回一次
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCreatedVO {
+    pub key_id: i32,
+    pub key_name: String,
+    pub key: String,
+    pub key_prefix: String,
+    pub expire_time: Option<OffsetDateTime>,
+}
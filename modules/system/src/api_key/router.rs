@@ -0,0 +1,12 @@
+use salvo::Router;
+
+use crate::api_key::handle;
+
+/// 当前用户管理自己的 API Key（仅限登录会话，不能用 API Key 本身创建或吊销）
+pub fn init_router() -> Router {
+    Router::new()
+        .path("api_key")
+        .push(Router::with_path("add").post(handle::add))
+        .push(Router::with_path("list").get(handle::list))
+        .push(Router::with_path("revoke/{key_id}").get(handle::revoke))
+}
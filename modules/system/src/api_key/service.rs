@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use common::{AppError, AppResult, constants::Permission};
+use framework::{
+    api_key::{ApiKeyRecord, ApiKeyStore, ApiKeyTool},
+    permission::PermTool,
+};
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use tracing::info;
+
+use crate::api_key::model::{ApiKeyAddDTO, ApiKeyCreatedVO, ApiKeyVO};
+
+/// 创建 API Key，权限范围不能超出用户本人拥有的权限
+pub(crate) async fn add(
+    db: &PgPool,
+    perm_tool: &PermTool,
+    user_id: i32,
+    dto: ApiKeyAddDTO,
+) -> AppResult<ApiKeyCreatedVO> {
+    info!(
+        "[SERVICE] Entering api_key::add with user_id: {}, name: {}, scopes: {:?}",
+        user_id, dto.key_name, dto.scopes
+    );
+    if dto.key_name.trim().is_empty() {
+        return Err(AppError::ValidationFailed("名称不能为空".to_string()));
+    }
+    if dto.scopes.is_empty() {
+        return Err(AppError::ValidationFailed("请至少选择一个权限".to_string()));
+    }
+    for scope in &dto.scopes {
+        let perm = Permission::from_str(scope)
+            .map_err(|_| AppError::ValidationFailed(format!("未知的权限标识: {scope}")))?;
+        if !perm_tool.has_permission(user_id, perm).await? {
+            return Err(AppError::ValidationFailed(format!("没有权限授予: {scope}")));
+        }
+    }
+    let expire_time = dto
+        .expire_days
+        .map(|days| OffsetDateTime::now_utc() + Duration::days(days as i64));
+
+    let new_key = ApiKeyTool::generate()?;
+    let key_id = sqlx::query_scalar!(
+        "INSERT INTO sys_api_key (user_id, key_name, key_prefix, key_hash, scopes, expire_time)
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING key_id",
+        user_id,
+        dto.key_name,
+        new_key.prefix,
+        new_key.key_hash,
+        &dto.scopes,
+        expire_time
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(ApiKeyCreatedVO {
+        key_id,
+        key_name: dto.key_name,
+        key: new_key.key,
+        key_prefix: new_key.prefix,
+        expire_time,
+    })
+}
+
+/// 查询用户的 API Key 列表
+pub(crate) async fn list(db: &PgPool, user_id: i32) -> AppResult<Vec<ApiKeyVO>> {
+    info!("[SERVICE] Entering api_key::list with user_id: {}", user_id);
+    let keys = sqlx::query_as!(
+        ApiKeyVO,
+        "SELECT key_id, key_name, key_prefix, scopes, expire_time, last_used_time, revoked, create_time
+         FROM sys_api_key WHERE user_id = $1 ORDER BY create_time DESC",
+        user_id
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(keys)
+}
+
+/// 吊销用户本人的 API Key，返回其前缀
+pub(crate) async fn revoke(db: &PgPool, user_id: i32, key_id: i32) -> AppResult<String> {
+    info!(
+        "[SERVICE] Entering api_key::revoke with user_id: {}, key_id: {}",
+        user_id, key_id
+    );
+    sqlx::query_scalar!(
+        "UPDATE sys_api_key SET revoked = '1' WHERE key_id = $1 AND user_id = $2 AND revoked = '0' RETURNING key_prefix",
+        key_id,
+        user_id
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or(AppError::RecordNotFound)
+}
+
+/// API Key 存储，供认证中间件使用
+pub struct SysApiKeyStore {
+    db: PgPool,
+}
+
+impl SysApiKeyStore {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ApiKeyStore for SysApiKeyStore {
+    async fn find(&self, prefix: &str) -> AppResult<Option<ApiKeyRecord>> {
+        let record = sqlx::query!(
+            "SELECT k.key_id, k.user_id, k.key_hash, k.scopes, k.expire_time FROM sys_api_key k
+             INNER JOIN sys_user u ON k.user_id = u.user_id
+             WHERE k.key_prefix = $1 AND k.revoked = '0' AND u.status = '0' AND u.del_flag = '0'",
+            prefix
+        )
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(record.map(|r| ApiKeyRecord {
+            key_id: r.key_id,
+            user_id: r.user_id,
+            key_hash: r.key_hash,
+            scopes: r.scopes.into_iter().collect(),
+            expire_time: r.expire_time,
+        }))
+    }
+
+    async fn touch(&self, key_id: i32) -> AppResult<()> {
+        sqlx::query!(
+            "UPDATE sys_api_key SET last_used_time = NOW() WHERE key_id = $1",
+            key_id
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use common::{AppResult, response::ResponseResult};
+use framework::state::Db;
+use monitor::operlog::model::BusinessType;
+use salvo::oapi::endpoint;
+use salvo::oapi::extract::{JsonBody, PathParam, QueryParam};
+use salvo::{Depot, Writer};
+use tracing::info;
+
+use crate::dept::model::{DeptDTO, DeptTreeVo, ListDeptQuery, SysDept};
+use crate::dept::service;
+use crate::handle::{LogMeta, current_user_id};
+
+/// 部门列表
+#[endpoint(tags("部门管理"), summary = "部门列表")]
+pub async fn list(
+    dept_name: QueryParam<String, false>,
+    status: QueryParam<String, false>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<Vec<SysDept>>> {
+    info!("[HANDLER] Entering dept::list");
+    let query = ListDeptQuery {
+        dept_name: dept_name.into_inner(),
+        status: status.into_inner(),
+    };
+    let data_scope = service::select_data_scope(&db, current_user_id(depot)?).await?;
+    let depts = service::select_dept_list(&db, query, &data_scope).await?;
+    ResponseResult::success(depts).into()
+}
+
+/// 部门树
+#[endpoint(tags("部门管理"), summary = "部门树")]
+pub async fn tree(depot: &mut Depot, db: Db) -> AppResult<ResponseResult<Vec<DeptTreeVo>>> {
+    info!("[HANDLER] Entering dept::tree");
+    let query = ListDeptQuery {
+        status: Some("0".to_string()),
+        ..Default::default()
+    };
+    let data_scope = service::select_data_scope(&db, current_user_id(depot)?).await?;
+    let depts = service::select_dept_list(&db, query, &data_scope).await?;
+    ResponseResult::success(DeptTreeVo::build_dept_tree(depts)).into()
+}
+
+/// 部门详情
+#[endpoint(tags("部门管理"), summary = "部门详情")]
+pub async fn get_detail(dept_id: PathParam<i32>, db: Db) -> AppResult<ResponseResult<SysDept>> {
+    let dept_id = dept_id.into_inner();
+    info!(
+        "[HANDLER] Entering dept::get_detail with dept_id: {}",
+        dept_id
+    );
+    let dept = service::select_dept_by_id(&db, dept_id).await?;
+    ResponseResult::success(dept).into()
+}
+
+/// 新增部门
+#[endpoint(tags("部门管理"), summary = "新增部门")]
+pub async fn add(
+    dept: JsonBody<DeptDTO>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let dept = dept.into_inner();
+    info!("[HANDLER] Entering dept::add with body: {:?}", dept);
+    LogMeta::set(depot, "部门管理", BusinessType::Add.get_value(), "新增部门");
+    service::add_dept(&db, dept).await?;
+    ResponseResult::success_msg("新增成功").into()
+}
+
+/// 修改部门
+#[endpoint(tags("部门管理"), summary = "修改部门")]
+pub async fn update(
+    dept: JsonBody<DeptDTO>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let dept = dept.into_inner();
+    info!("[HANDLER] Entering dept::update with body: {:?}", dept);
+    LogMeta::set(
+        depot,
+        "部门管理",
+        BusinessType::Update.get_value(),
+        "修改部门",
+    );
+    service::update_dept(&db, dept).await?;
+    ResponseResult::success_msg("修改成功").into()
+}
+
+/// 删除部门
+#[endpoint(tags("部门管理"), summary = "删除部门")]
+pub async fn delete(
+    dept_id: PathParam<i32>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let dept_id = dept_id.into_inner();
+    info!("[HANDLER] Entering dept::delete with dept_id: {}", dept_id);
+    LogMeta::set(
+        depot,
+        "部门管理",
+        BusinessType::Delete.get_value(),
+        "删除部门",
+    );
+    service::delete_dept(&db, dept_id).await?;
+    ResponseResult::success_msg("删除成功").into()
+}
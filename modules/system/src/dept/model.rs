@@ -0,0 +1,123 @@
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+/// 部门表对应的结构体
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SysDept {
+    /// 部门ID
+    pub dept_id: i32,
+    /// 父部门ID（0表示根部门）
+    pub parent_id: i32,
+    /// 祖级列表（逗号分隔，如 0,1,3）
+    pub ancestors: String,
+    /// 部门名称
+    pub dept_name: String,
+    /// 显示顺序
+    pub order_num: i32,
+    /// 负责人
+    pub leader: Option<String>,
+    /// 联系电话
+    pub phone: Option<String>,
+    /// 邮箱
+    pub email: Option<String>,
+    /// 部门状态（0正常 1停用）
+    pub status: String,
+    #[serde(skip_serializing)]
+    pub del_flag: String,
+    pub create_by: Option<String>,
+    pub create_time: Option<OffsetDateTime>,
+    pub update_by: Option<String>,
+    pub update_time: Option<OffsetDateTime>,
+}
+
+/// 部门树
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeptTreeVo {
+    #[serde(flatten)]
+    pub dept: SysDept,
+    pub children: Vec<DeptTreeVo>,
+}
+
+impl DeptTreeVo {
+    /// 由部门列表构建部门树，父部门不在列表中的部门作为根节点
+    pub fn build_dept_tree(all_depts: Vec<SysDept>) -> Vec<Self> {
+        let ids: Vec<i32> = all_depts.iter().map(|d| d.dept_id).collect();
+        all_depts
+            .iter()
+            .filter(|d| !ids.contains(&d.parent_id))
+            .map(|dept| Self::build_inner(dept, &all_depts))
+            .collect()
+    }
+
+    fn build_inner(dept: &SysDept, all_depts: &[SysDept]) -> Self {
+        Self {
+            dept: dept.clone(),
+            children: all_depts
+                .iter()
+                .filter(|d| d.parent_id == dept.dept_id)
+                .map(|d| Self::build_inner(d, all_depts))
+                .collect(),
+        }
+    }
+}
+
+/// 部门列表查询参数
+#[derive(Deserialize, Debug, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeptQuery {
+    pub dept_name: Option<String>,
+    pub status: Option<String>,
+}
+
+/// 新增/修改部门时接收前端数据的请求体
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeptDTO {
+    /// 修改时必须携带ID
+    pub dept_id: Option<i32>,
+    pub parent_id: i32,
+    pub dept_name: String,
+    pub order_num: Option<i32>,
+    pub leader: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub status: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DeptTreeVo, SysDept};
+
+    fn dept(dept_id: i32, parent_id: i32) -> SysDept {
+        SysDept {
+            dept_id,
+            parent_id,
+            ancestors: String::new(),
+            dept_name: format!("dept{dept_id}"),
+            order_num: 0,
+            leader: None,
+            phone: None,
+            email: None,
+            status: "0".to_string(),
+            del_flag: "0".to_string(),
+            create_by: None,
+            create_time: None,
+            update_by: None,
+            update_time: None,
+        }
+    }
+
+    #[test]
+    fn build_dept_tree_test() {
+        let tree =
+            DeptTreeVo::build_dept_tree(vec![dept(1, 0), dept(2, 1), dept(3, 2), dept(5, 4)]);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].children[0].children[0].dept.dept_id, 3);
+        // 父部门被过滤掉时，子部门作为根节点
+        assert_eq!(tree[1].dept.dept_id, 5);
+    }
+}
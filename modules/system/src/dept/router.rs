@@ -0,0 +1,40 @@
+use common::constants::Permission;
+use framework::midddleware::require_perm;
+use salvo::Router;
+
+use crate::dept::handle::{add, delete, get_detail, list, tree, update};
+
+pub fn init_router() -> Router {
+    Router::new()
+        .path("dept")
+        .push(
+            Router::with_path("list")
+                .hoop(require_perm(Permission::DeptList))
+                .get(list),
+        )
+        .push(
+            Router::with_path("tree")
+                .hoop(require_perm(Permission::DeptList))
+                .get(tree),
+        )
+        .push(
+            Router::with_path("add")
+                .hoop(require_perm(Permission::DeptAdd))
+                .post(add),
+        )
+        .push(
+            Router::with_path("update")
+                .hoop(require_perm(Permission::DeptEdit))
+                .put(update),
+        )
+        .push(
+            Router::with_path("delete/{dept_id}")
+                .hoop(require_perm(Permission::DeptRemove))
+                .delete(delete),
+        )
+        .push(
+            Router::with_path("{dept_id}")
+                .hoop(require_perm(Permission::DeptQuery))
+                .get(get_detail),
+        )
+}
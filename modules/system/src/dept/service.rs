@@ -0,0 +1,275 @@
+use common::{AppError, AppResult, DataScope, SqlBuilder};
+use sqlx::PgPool;
+use tracing::info;
+
+use crate::dept::model::{DeptDTO, ListDeptQuery, SysDept};
+
+/// 根据条件查询数据权限范围内的部门列表
+pub(crate) async fn select_dept_list(
+    db: &PgPool,
+    query: ListDeptQuery,
+    data_scope: &DataScope,
+) -> AppResult<Vec<SysDept>> {
+    info!(
+        "[SERVICE] Entering select_dept_list with query: {:?}, data_scope: {:?}",
+        query, data_scope
+    );
+    let mut sql_builder = SqlBuilder::new(db, "select * from sys_dept where del_flag = '0'");
+    sql_builder
+        .where_data_scope(data_scope, "dept_id", None)
+        .where_like("dept_name", query.dept_name.as_deref())
+        .where_eq("status", query.status)
+        .order_by("parent_id", None)
+        .and_order_by("order_num", None);
+    sql_builder.fetch_all().await
+}
+
+/// 根据部门ID查询部门详情
+pub(crate) async fn select_dept_by_id(db: &PgPool, dept_id: i32) -> AppResult<SysDept> {
+    info!(
+        "[SERVICE] Entering select_dept_by_id with dept_id: {}",
+        dept_id
+    );
+    sqlx::query_as!(
+        SysDept,
+        "SELECT * FROM sys_dept WHERE dept_id = $1 AND del_flag = '0'",
+        dept_id
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or(AppError::RecordNotFound)
+}
+
+/// 查询部门及其所有下级部门的ID
+pub(crate) async fn select_dept_and_children_ids(db: &PgPool, dept_id: i32) -> AppResult<Vec<i32>> {
+    let dept_ids = sqlx::query_scalar!(
+        r#"SELECT dept_id FROM sys_dept
+           WHERE del_flag = '0' AND (dept_id = $1 OR $1::text = ANY(string_to_array(ancestors, ',')))"#,
+        dept_id
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(dept_ids)
+}
+
+/// 同一父部门下名称是否已存在
+async fn dept_name_exists(
+    db: &PgPool,
+    parent_id: i32,
+    dept_name: &str,
+    exclude_dept_id: Option<i32>,
+) -> AppResult<bool> {
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM sys_dept
+            WHERE parent_id = $1 AND dept_name = $2 AND del_flag = '0' AND dept_id <> COALESCE($3, 0)
+        ) AS "exists!""#,
+        parent_id,
+        dept_name,
+        exclude_dept_id
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(exists)
+}
+
+/// 计算新部门的祖级列表
+async fn build_ancestors(db: &PgPool, parent_id: i32) -> AppResult<String> {
+    if parent_id == 0 {
+        return Ok("0".to_string());
+    }
+    let parent = select_dept_by_id(db, parent_id).await?;
+    if parent.status != "0" {
+        return Err(AppError::ValidationFailed(
+            "上级部门已停用，不允许新增子部门".to_string(),
+        ));
+    }
+    Ok(format!("{},{}", parent.ancestors, parent.dept_id))
+}
+
+/// 新增部门
+pub(crate) async fn add_dept(db: &PgPool, dept: DeptDTO) -> AppResult<i32> {
+    info!("[SERVICE] Entering add_dept with dto: {:?}", dept);
+    if dept_name_exists(db, dept.parent_id, &dept.dept_name, None).await? {
+        return Err(AppError::ValidationFailed("部门名称已存在".to_string()));
+    }
+    let ancestors = build_ancestors(db, dept.parent_id).await?;
+    let dept_id = sqlx::query_scalar!(
+        r#"
+            INSERT INTO sys_dept (parent_id, ancestors, dept_name, order_num, leader, phone, email, status, create_by, create_time)
+            VALUES ($1, $2, $3, COALESCE($4, 0), $5, $6, $7, COALESCE($8, '0'), 'admin', NOW())
+            RETURNING dept_id
+        "#,
+        dept.parent_id,
+        ancestors,
+        dept.dept_name,
+        dept.order_num,
+        dept.leader,
+        dept.phone,
+        dept.email,
+        dept.status
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(dept_id)
+}
+
+/// 修改部门，上级部门变化时同步更新所有子孙部门的祖级列表（事务性）
+pub(crate) async fn update_dept(db: &PgPool, dept: DeptDTO) -> AppResult<u64> {
+    info!("[SERVICE] Entering update_dept with dto: {:?}", dept);
+    let dept_id = dept
+        .dept_id
+        .ok_or(AppError::ValidationFailed("部门ID不能为空".to_string()))?;
+    if dept.parent_id == dept_id {
+        return Err(AppError::ValidationFailed("上级部门不能是自己".to_string()));
+    }
+    let old = select_dept_by_id(db, dept_id).await?;
+    if dept_name_exists(db, dept.parent_id, &dept.dept_name, Some(dept_id)).await? {
+        return Err(AppError::ValidationFailed("部门名称已存在".to_string()));
+    }
+    let ancestors = if dept.parent_id == old.parent_id {
+        old.ancestors.clone()
+    } else {
+        let ancestors = build_ancestors(db, dept.parent_id).await?;
+        if ancestors.split(',').any(|id| id == dept_id.to_string()) {
+            return Err(AppError::ValidationFailed(
+                "上级部门不能是自己的下级部门".to_string(),
+            ));
+        }
+        ancestors
+    };
+
+    let mut tx = db.begin().await?;
+    let result = sqlx::query!(
+        r#"
+            UPDATE sys_dept
+            SET parent_id = $1, ancestors = $2, dept_name = $3, order_num = COALESCE($4, order_num), leader = $5, phone = $6, email = $7,
+                status = COALESCE($8, status), update_by = 'admin', update_time = NOW()
+            WHERE dept_id = $9
+        "#,
+        dept.parent_id,
+        ancestors,
+        dept.dept_name,
+        dept.order_num,
+        dept.leader,
+        dept.phone,
+        dept.email,
+        dept.status,
+        dept_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    if ancestors != old.ancestors {
+        // 子孙部门的祖级列表以 "旧祖级列表,本部门ID" 开头，替换为新的前缀
+        let old_prefix = format!("{},{}", old.ancestors, dept_id);
+        let new_prefix = format!("{},{}", ancestors, dept_id);
+        let children = sqlx::query!(
+            r#"
+                UPDATE sys_dept SET ancestors = $2 || substr(ancestors, length($1) + 1)
+                WHERE ancestors = $1 OR ancestors LIKE $1 || ',%'
+            "#,
+            old_prefix,
+            new_prefix
+        )
+        .execute(&mut *tx)
+        .await?;
+        info!(
+            "[SERVICE] Moved dept {} from '{}' to '{}', {} descendants updated.",
+            dept_id,
+            old.ancestors,
+            ancestors,
+            children.rows_affected()
+        );
+    }
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// 删除部门（存在子部门或用户时不允许删除）
+pub(crate) async fn delete_dept(db: &PgPool, dept_id: i32) -> AppResult<u64> {
+    info!("[SERVICE] Entering delete_dept with dept_id: {}", dept_id);
+    let has_children = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM sys_dept WHERE parent_id = $1 AND del_flag = '0') AS "exists!""#,
+        dept_id
+    )
+    .fetch_one(db)
+    .await?;
+    if has_children {
+        return Err(AppError::ValidationFailed(
+            "存在下级部门，不允许删除".to_string(),
+        ));
+    }
+    let has_users = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM sys_user WHERE dept_id = $1 AND del_flag = '0') AS "exists!""#,
+        dept_id
+    )
+    .fetch_one(db)
+    .await?;
+    if has_users {
+        return Err(AppError::ValidationFailed(
+            "部门存在用户，不允许删除".to_string(),
+        ));
+    }
+    let result = sqlx::query!(
+        "UPDATE sys_dept SET del_flag = '1', update_by = 'admin', update_time = NOW() WHERE dept_id = $1",
+        dept_id
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// 根据用户所属角色的数据范围计算可访问的部门
+///
+/// 超级管理员或任一角色为全部数据权限时返回 [`DataScope::All`]；
+/// 否则合并各角色的自定义部门、本部门、本部门及以下部门，本人的数据始终可见。
+pub async fn select_data_scope(db: &PgPool, user_id: i32) -> AppResult<DataScope> {
+    if user_id == 1 {
+        return Ok(DataScope::All);
+    }
+    let roles = sqlx::query!(
+        r#"SELECT sr.role_id, sr.data_scope FROM sys_role sr
+           INNER JOIN sys_user_role sur ON sr.role_id = sur.role_id
+           WHERE sur.user_id = $1 AND sr.status = '0' AND sr.del_flag = '0'"#,
+        user_id
+    )
+    .fetch_all(db)
+    .await?;
+    // data_scope 为空时按数据库默认值（全部数据权限）处理
+    let scopes: Vec<(i32, &str)> = roles
+        .iter()
+        .map(|r| (r.role_id, r.data_scope.as_deref().unwrap_or("1")))
+        .collect();
+    if scopes.iter().any(|(_, scope)| *scope == "1") {
+        return Ok(DataScope::All);
+    }
+    let custom_role_ids: Vec<i32> = scopes
+        .iter()
+        .filter(|(_, scope)| *scope == "2")
+        .map(|(role_id, _)| *role_id)
+        .collect();
+    let with_children = scopes.iter().any(|(_, scope)| *scope == "4");
+    let own_dept = with_children || scopes.iter().any(|(_, scope)| *scope == "3");
+
+    let dept_ids = sqlx::query_scalar!(
+        r#"
+            SELECT d.dept_id FROM sys_dept d, (SELECT dept_id FROM sys_user WHERE user_id = $1) u
+            WHERE d.del_flag = '0' AND (
+                d.dept_id IN (SELECT dept_id FROM sys_role_dept WHERE role_id = ANY($2))
+                OR ($3 AND d.dept_id = u.dept_id)
+                OR ($4 AND u.dept_id::text = ANY(string_to_array(d.ancestors, ',')))
+            )
+        "#,
+        user_id,
+        &custom_role_ids,
+        own_dept,
+        with_children
+    )
+    .fetch_all(db)
+    .await?;
+    info!(
+        "[SERVICE] Data scope of user_id {}: depts {:?}",
+        user_id, dept_ids
+    );
+    Ok(DataScope::Limited { user_id, dept_ids })
+}
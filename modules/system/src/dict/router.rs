@@ -1,3 +1,5 @@
+use common::constants::Permission;
+use framework::midddleware::{interactive_only, require_perm};
 use salvo::Router;
 
 use crate::dict::handle;
@@ -8,13 +10,30 @@ pub fn init_router() -> Router {
         .push(
             Router::new()
                 .path("type")
-                .push(Router::with_path("/page").post(handle::get_type_page))
-                .push(Router::with_path("/add").post(handle::add_dict_type)),
+                .push(
+                    Router::with_path("/page")
+                        .hoop(require_perm(Permission::DictList))
+                        .post(handle::get_type_page),
+                )
+                .push(
+                    Router::with_path("/add")
+                        .hoop(require_perm(Permission::DictAdd))
+                        .post(handle::add_dict_type),
+                ),
         )
         .push(
             Router::new()
                 .path("data")
-                .push(Router::with_path("/list_by_type").get(handle::get_data_list_by_type))
-                .push(Router::with_path("/add").post(handle::add_dict_data)),
+                //前端下拉框等需要，登录即可访问（API Key 不能访问）
+                .push(
+                    Router::with_path("/list_by_type")
+                        .hoop(interactive_only)
+                        .get(handle::get_data_list_by_type),
+                )
+                .push(
+                    Router::with_path("/add")
+                        .hoop(require_perm(Permission::DictAdd))
+                        .post(handle::add_dict_data),
+                ),
         )
 }
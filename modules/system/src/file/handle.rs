@@ -1,13 +1,15 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use common::{AppError, AppResult, response::ResponseResult};
-use framework::config::Upload;
+use framework::{config::Upload, state::Config};
 use salvo::{fs::NamedFile, http::form::FilePart, oapi::extract::QueryParam, prelude::*};
 use time::OffsetDateTime;
 use tracing::{error, info};
 
-use crate::file::UploadTool;
-
 #[handler]
 pub async fn index(res: &mut Response) {
     res.render(Text::Html(
@@ -28,15 +30,12 @@ pub async fn index(res: &mut Response) {
 }
 
 #[endpoint(tags("上传"), summary = "上传文件")]
-pub async fn upload(req: &mut Request, res: &mut Response) {
+pub async fn upload(req: &mut Request, res: &mut Response, config: Config) {
     const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB
 
     let mut map = HashMap::new();
-    let ul = UploadTool::get()
-        .map_err(|e| res.render(Text::Plain(e.to_string())))
-        .unwrap();
+    let ul = &config.upload;
 
-    let file_path_prf = &ul.path;
     match req.form_data().await {
         Ok(data) => {
             // 处理文件
@@ -57,35 +56,14 @@ pub async fn upload(req: &mut Request, res: &mut Response) {
                             res.render(Text::Plain(format!("{} 文件大小超过限制", file_name)));
                             break;
                         }
-                        if let Err(e) = check(file, ul) {
-                            println!("校验失败: {}", e);
-                            return res.render(Json(ResponseResult::error(400, &e.to_string())));
-                        }
-
-                        let sf = sanitize_filename(file_name);
-
-                        let now = OffsetDateTime::now_utc();
-                        let date = now.date();
-                        let path_prf_str = format!("{}{}", file_path_prf, date);
-                        info!("Saving uploaded file to: {}", path_prf_str);
-
-                        let path_prf = Path::new(&path_prf_str);
-                        if !path_prf.exists() {
-                            fs::create_dir_all(path_prf).expect("创建文件夹失败");
-                        }
-
-                        let pb = path_prf.join(&sf);
-
-                        match std::fs::copy(file.path(), &pb) {
-                            Ok(_) => {
-                                map.insert(file_name, pb.clone());
-                                info!("文件上传成功: {:?}", pb);
+                        match save(file, ul) {
+                            Ok(pb) => {
+                                map.insert(file_name, pb);
                             }
                             Err(e) => {
                                 error!("{}", e);
-                                res.status_code(StatusCode::BAD_REQUEST);
-                                res.render(Text::Plain(e.to_string()));
-                                break;
+                                return res
+                                    .render(Json(ResponseResult::error(400, &e.to_string())));
                             }
                         }
                     }
@@ -129,6 +107,27 @@ pub async fn get(path: QueryParam<String>, req: &mut Request, res: &mut Response
     }
 }
 
+/// 校验并保存上传的文件，按日期分目录存放，返回保存路径
+pub(crate) fn save(file: &FilePart, upload_setting: &Upload) -> AppResult<PathBuf> {
+    check(file, upload_setting)?;
+    let sf = sanitize_filename(file.name().unwrap_or("未知文件名"));
+
+    let date = OffsetDateTime::now_utc().date();
+    let path_prf_str = format!("{}{}", upload_setting.path, date);
+    info!("Saving uploaded file to: {}", path_prf_str);
+
+    let path_prf = Path::new(&path_prf_str);
+    if !path_prf.exists() {
+        fs::create_dir_all(path_prf)
+            .map_err(|e| AppError::Other(format!("创建文件夹失败: {e}")))?;
+    }
+
+    let pb = path_prf.join(&sf);
+    fs::copy(file.path(), &pb).map_err(|e| AppError::Other(e.to_string()))?;
+    info!("文件上传成功: {:?}", pb);
+    Ok(pb)
+}
+
 fn check(file: &FilePart, upload_setting: &Upload) -> AppResult<()> {
     // 获取文件 Content-Type，无类型则返回错误
     let content_type = file
@@ -1,4 +1,4 @@
-use framework::midddleware::auth;
+use framework::midddleware::{auth, interactive_only};
 use salvo::Router;
 
 use crate::file::handle::{get, index, upload};
@@ -10,6 +10,8 @@ pub fn init_router() -> Router {
         .push(
             Router::new()
                 .hoop(auth)
+                //仅限登录会话，API Key 不能访问
+                .hoop(interactive_only)
                 .push(Router::with_path("upload").post(upload))
                 .push(Router::with_path("index").get(index)),
         )
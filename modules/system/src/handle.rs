@@ -1,34 +1,55 @@
 use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use captcha::Captcha;
+use common::models::sys_user_online::SysUserOnline;
 use common::response::ResponseResult;
 use common::{AppError, AppResult};
-use framework::db::DBPool;
-use framework::jwt::{CLAIMS, Claims, JWTTool, TokenType};
+use framework::background::BackgroundTasks;
+use framework::client_ip::client_ip;
+use framework::ip_location::IpLocationTool;
+use framework::jwt::{CLAIMS, Claims, JwtAuthUtil, TokenType};
+use framework::login_lock::LoginLockTool;
+use framework::metrics::{Counter, Gauge, MetricsTool};
+use framework::password::PasswordPolicy;
+use framework::request_id::REQUEST_ID;
+use framework::session::SessionCache;
+use framework::state::{AppState, Db, Ext, Jwt, State};
+pub use monitor::operlog::model::LogMeta;
 use monitor::operlog::model::OperLogDTO;
 use monitor::{login_info, operlog};
 use salvo::Request;
 use salvo::oapi::endpoint;
-use salvo::oapi::extract::{JsonBody, QueryParam};
+use salvo::oapi::extract::JsonBody;
 use salvo::{Writer, handler};
-use serde_json::Value;
 use sqlx::PgPool;
 use time::OffsetDateTime;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use user_agent_parser::UserAgentParser;
 use uuid::Uuid;
 
-use crate::model::{CapCache, CaptchaDTO, CaptchaVO, LoginDTO, PASER, TokenVO};
-use crate::user::model::SysUserVO;
+use crate::mfa;
+use crate::model::{
+    CapCache, CaptchaDTO, CaptchaVO, ChallengeCache, ChallengeKind, ChangePasswordDTO,
+    LoginChallenge, LoginDTO, LoginVO, RegisterDTO, TokenVO,
+};
+use crate::user::model::{SysUser, SysUserVO};
 use crate::user::{self, service};
 
 use salvo::http::ResBody;
 use salvo::prelude::*;
-use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
+static LOGIN_TOTAL: Counter = Counter::new("login_total", "登录次数");
+/// 落盘任务类型
+const OPER_LOG_TASK: &str = "operlog";
+const LOGIN_LOG_TASK: &str = "login_log";
+
+static OPER_LOG_PENDING: Gauge = Gauge::new("operlog_pending", "等待写入数据库的操作日志数");
+
 /// 处理获取验证码图片
 #[endpoint(tags("登录"))]
-pub async fn get_captcha_image() -> AppResult<ResponseResult<CaptchaVO>> {
+pub async fn get_captcha_image(cap_cache: Ext<CapCache>) -> AppResult<ResponseResult<CaptchaVO>> {
     info!("[HANDLER] Entering get  captcha image");
     // 生成 4 位验证码
     let captcha_string: String;
@@ -54,9 +75,7 @@ pub async fn get_captcha_image() -> AppResult<ResponseResult<CaptchaVO>> {
     }
 
     let uuid = Uuid::new_v4().to_string();
-    CapCache::init_cache()
-        .insert(uuid.clone(), captcha_string)
-        .await;
+    cap_cache.insert(&uuid, &captcha_string).await;
     let captcha_vo = CaptchaVO {
         id: uuid,
         img: captcha_img,
@@ -67,16 +86,25 @@ pub async fn get_captcha_image() -> AppResult<ResponseResult<CaptchaVO>> {
 
 /// 刷新接口令牌
 #[endpoint(tags("登录"), summary = "刷新接口令牌")]
-pub async fn refresh_token_handler(req: &mut Request) -> AppResult<ResponseResult<TokenVO>> {
+pub async fn refresh_token_handler(
+    req: &mut Request,
+    jwt: Jwt,
+    sessions: Ext<SessionCache>,
+) -> AppResult<ResponseResult<TokenVO>> {
     info!("[HANDLER]  Entering  refresh token");
-    let token_vo = ref_token(req)?;
+    let token_vo = ref_token(req, &jwt, &sessions).await?;
     Ok(ResponseResult::success_with_msg("令牌刷新成功", token_vo))
 }
 
 ///刷新token
-fn ref_token(req: &mut Request) -> AppResult<TokenVO> {
+///
+///刷新令牌每次使用后轮换；若出现已轮换的旧刷新令牌（重放），吊销整个会话
+async fn ref_token(
+    req: &mut Request,
+    jwt_auth_util: &JwtAuthUtil,
+    sessions: &SessionCache,
+) -> AppResult<TokenVO> {
     const REFTOKEN: &str = "refreshToken";
-    let jwt_auth_util = JWTTool::get()?;
     let ref_token = req
         .cookies()
         .get(REFTOKEN)
@@ -85,9 +113,28 @@ fn ref_token(req: &mut Request) -> AppResult<TokenVO> {
 
     // 校验 Refresh Token
     let ref_claims = jwt_auth_util.verify_ref_token(ref_token)?;
-    // 若 Refresh Token 有效，生成新的 Access Token（刷新令牌可复用，或按需轮换）
-    let new_acc_token = jwt_auth_util.generate_token(ref_claims.sub, TokenType::Access)?;
-    let new_ref_token = jwt_auth_util.generate_token(ref_claims.sub, TokenType::Refresh)?;
+    // 会话已被移除（登出、强退）则不允许刷新
+    let mut session = sessions
+        .get(&ref_claims.sid)
+        .await
+        .ok_or(AppError::TokenInvalid)?;
+    if session.refresh_token != ref_token {
+        warn!(
+            "[HANDLER] Reuse of rotated refresh token detected, revoke session: {}",
+            ref_claims.sid
+        );
+        sessions.revoke(jwt_auth_util, &ref_claims.sid).await;
+        return Err(AppError::TokenInvalid);
+    }
+    // 轮换：签发新的 Access Token 与 Refresh Token
+    let new_acc_token =
+        jwt_auth_util.generate_token(ref_claims.sub, &ref_claims.sid, TokenType::Access)?;
+    let new_ref_token =
+        jwt_auth_util.generate_token(ref_claims.sub, &ref_claims.sid, TokenType::Refresh)?;
+    // 会话续期
+    session.token = new_acc_token.clone();
+    session.refresh_token = new_ref_token.clone();
+    sessions.insert(session).await;
     Ok(TokenVO {
         access_token: new_acc_token,
         refresh_token: new_ref_token,
@@ -99,68 +146,58 @@ fn ref_token(req: &mut Request) -> AppResult<TokenVO> {
 pub async fn login(
     login_dto: JsonBody<LoginDTO>,
     req: &mut Request,
-) -> AppResult<ResponseResult<TokenVO>> {
+    state: State,
+    cap_cache: Ext<CapCache>,
+    ua_parser: Ext<Arc<UserAgentParser>>,
+) -> AppResult<ResponseResult<LoginVO>> {
     info!("[HANDLER]  Entering login::with body:{:?}", login_dto);
-    // 获取客户端地址
-    let ipaddr = req
-        .remote_addr()
-        .as_ipv4()
-        .map(|addr| addr.ip().to_string())
-        .unwrap_or_default();
-    let mut os = None;
-    let mut browser = None;
-    req.headers()
-        .get("User-Agent")
-        .and_then(|agent| agent.to_str().ok())
-        .map(|s| {
-            let user_agent_parser =
-                PASER.get_or_init(|| UserAgentParser::from_path("regexes.yaml").expect(""));
-
-            os = user_agent_parser.parse_os(s).name.map(|s| s.to_string());
-            browser = user_agent_parser
-                .parse_product(s)
-                .name
-                .map(|s| s.to_string());
-        })
-        .unwrap();
+    let (ipaddr, os, browser) = client_info(req, &ua_parser);
 
     let LoginDTO {
         username,
         password,
-        captcha: CaptchaDTO { uuid, code },
+        captcha,
     } = login_dto.into_inner();
 
-    let db_pool = DBPool::get().await?;
+    // 1.0 登录锁定校验
+    let login_lock = state.ext::<LoginLockTool>()?;
+    if let Err(e) = login_lock.check(&username, &ipaddr).await {
+        record_login_log(
+            &state,
+            username.clone(),
+            ipaddr.clone(),
+            os,
+            browser,
+            "1",
+            "登录失败次数过多，账户已锁定".to_string(),
+        )
+        .await;
+        return Err(e);
+    }
     // 1.1 验证码校验
-    match CapCache::get(&uuid).await? {
-        Some(cache_code) if cache_code.to_lowercase() == code.to_lowercase() => {
-            //移除captcha
-            CapCache::remove(&uuid).await?;
-        }
-        _ => {
-            record_login_log(
-                db_pool,
-                username.clone(),
-                ipaddr.clone(),
-                os,
-                browser,
-                "1",
-                "验证码错误或已过期".to_string(),
-            )
-            .await;
-            return Err(AppError::CaptchaError);
-        }
+    if !check_captcha(&cap_cache, &captcha).await? {
+        record_login_log(
+            &state,
+            username.clone(),
+            ipaddr.clone(),
+            os,
+            browser,
+            "1",
+            "验证码错误或已过期".to_string(),
+        )
+        .await;
+        return Err(AppError::CaptchaError);
     }
-    let db = DBPool::get().await?;
 
     //账号密码校验
-    let user = service::select_user_by_username(db, &username).await?;
+    let user = service::select_user_by_username(state.db(), &username).await?;
     let user = match user {
         Some(user) => user,
         None => {
             error!("[LOGIN_HANDLER] 用户 '{}' 不存在.", &username);
+            login_lock.record_failure(&username, &ipaddr).await;
             record_login_log(
-                db_pool,
+                &state,
                 username.clone(),
                 ipaddr.clone(),
                 os,
@@ -173,7 +210,7 @@ pub async fn login(
         }
     };
 
-    let password_from_db = if let Some(pwd) = user.password {
+    let password_from_db = if let Some(pwd) = user.password.clone() {
         pwd
     } else {
         // 如果密码是 None，记录日志并返回错误
@@ -182,42 +219,48 @@ pub async fn login(
             username
         );
 
-        tokio::spawn(record_login_log(
-            db_pool,
+        record_login_log(
+            &state,
             username.clone(),
             ipaddr.clone(),
             os,
             browser,
             "1",
             "服务端密码处理错误".to_string(),
-        ));
+        )
+        .await;
 
         return Err(AppError::InvalidCredentials)?;
     };
 
-    let parsed_hash = PasswordHash::new(&password_from_db).map_err(|e| {
-        error!(
-            "[LOGIN_HANDLER] 密码哈希字符串解析失败! Error: {}. Hash: '{}'",
-            e, password_from_db
-        );
-        tokio::spawn(record_login_log(
-            db_pool,
-            username.clone(),
-            ipaddr.clone(),
-            os.clone(),
-            browser.clone(),
-            "1",
-            "服务端密码处理错误".to_string(),
-        ));
-        AppError::Other(e.to_string())
-    })?;
+    let parsed_hash = match PasswordHash::new(&password_from_db) {
+        Ok(parsed_hash) => parsed_hash,
+        Err(e) => {
+            error!(
+                "[LOGIN_HANDLER] 密码哈希字符串解析失败! Error: {}. Hash: '{}'",
+                e, password_from_db
+            );
+            record_login_log(
+                &state,
+                username.clone(),
+                ipaddr.clone(),
+                os,
+                browser,
+                "1",
+                "服务端密码处理错误".to_string(),
+            )
+            .await;
+            return Err(AppError::Other(e.to_string()));
+        }
+    };
 
     if Argon2::default()
         .verify_password(password.as_bytes(), &parsed_hash)
         .is_err()
     {
+        login_lock.record_failure(&username, &ipaddr).await;
         record_login_log(
-            db_pool,
+            &state,
             username.clone(),
             ipaddr.clone(),
             os.clone(),
@@ -228,64 +271,303 @@ pub async fn login(
         .await;
         Err(AppError::InvalidCredentials)?
     }
-    // 生成jwt
-    let jwt_auth_util = JWTTool::get()?;
-    let acc_token = jwt_auth_util.generate_token(user.user_id, TokenType::Access)?;
-    let ref_token = jwt_auth_util.generate_token(user.user_id, TokenType::Refresh)?;
+    login_lock.reset(&username).await;
+    // 停用或注册待审核的账号不允许登录
+    if user.status.as_deref() != Some("0") {
+        record_login_log(
+            &state,
+            username.clone(),
+            ipaddr.clone(),
+            os,
+            browser,
+            "1",
+            "账号已停用或待审核".to_string(),
+        )
+        .await;
+        return Err(AppError::ValidationFailed(
+            "账号已停用或正在等待审核".to_string(),
+        ));
+    }
+    continue_login(&state, &user, ipaddr, os, browser, true).await
+}
 
-    let token_vo = TokenVO {
-        access_token: acc_token,
-        refresh_token: ref_token,
+/// 查询 IP 归属地
+pub(crate) async fn ip_location(state: &AppState, ip: &str) -> Option<String> {
+    state.ext::<IpLocationTool>().ok()?.location(ip).await
+}
+
+/// 获取客户端地址及 User-Agent 中的操作系统、浏览器
+pub(crate) fn client_info(
+    req: &Request,
+    user_agent_parser: &UserAgentParser,
+) -> (String, Option<String>, Option<String>) {
+    let ipaddr = client_ip(req);
+    let Some(agent) = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|agent| agent.to_str().ok())
+    else {
+        return (ipaddr, None, None);
     };
+    let os = user_agent_parser
+        .parse_os(agent)
+        .name
+        .map(|s| s.to_string());
+    let browser = user_agent_parser
+        .parse_product(agent)
+        .name
+        .map(|s| s.to_string());
+    (ipaddr, os, browser)
+}
+
+/// 校验验证码，校验通过后移除，防止重复使用
+async fn check_captcha(cap_cache: &CapCache, captcha: &CaptchaDTO) -> AppResult<bool> {
+    match cap_cache.get(&captcha.uuid).await {
+        Some(cache_code) if cache_code.to_lowercase() == captcha.code.to_lowercase() => {
+            cap_cache.remove(&captcha.uuid).await;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
 
+/// 登录时修改过期密码，修改成功后继续完成登录
+#[endpoint(tags("登录"), summary = "登录时修改过期密码")]
+pub async fn login_change_password(
+    dto: JsonBody<ChangePasswordDTO>,
+    state: State,
+    challenges: Ext<ChallengeCache>,
+    policy: Ext<PasswordPolicy>,
+) -> AppResult<ResponseResult<LoginVO>> {
+    info!("[HANDLER] Entering login_change_password");
+    let ChangePasswordDTO {
+        challenge_token,
+        new_password,
+    } = dto.into_inner();
+    let challenge = challenges
+        .get(&challenge_token, ChallengeKind::ChangePassword)
+        .await?;
+    service::reset_user_password(state.db(), &policy, challenge.user_id, &new_password).await?;
+    challenges.remove(&challenge_token).await;
+    let user = service::select_user_entity(state.db(), challenge.user_id).await?;
+    continue_login(
+        &state,
+        &user,
+        challenge.ipaddr,
+        challenge.os,
+        challenge.browser,
+        false,
+    )
+    .await
+}
+
+/// 密码校验通过后的后续步骤：密码过期需先修改，启用或被要求双因素认证需先校验或绑定，否则直接签发令牌
+pub(crate) async fn continue_login(
+    state: &AppState,
+    user: &SysUser,
+    ipaddr: String,
+    os: Option<String>,
+    browser: Option<String>,
+    check_expiry: bool,
+) -> AppResult<ResponseResult<LoginVO>> {
+    let pwd_update_date = user
+        .pwd_update_date
+        .or(user.create_time)
+        .unwrap_or_else(OffsetDateTime::now_utc);
+    let db = state.db();
+    let challenge = if check_expiry && state.ext::<PasswordPolicy>()?.is_expired(pwd_update_date) {
+        Some((ChallengeKind::ChangePassword, "密码已过期，请修改密码"))
+    } else if mfa::service::is_enabled(user) {
+        Some((ChallengeKind::Verify, "请完成双因素认证"))
+    } else if mfa::service::is_mfa_required(db, user.user_id).await? {
+        Some((ChallengeKind::Enroll, "请绑定双因素认证"))
+    } else {
+        None
+    };
+    if let Some((kind, msg)) = challenge {
+        let challenge_token = Uuid::new_v4().to_string();
+        state
+            .ext::<ChallengeCache>()?
+            .insert(
+                &challenge_token,
+                LoginChallenge {
+                    kind,
+                    user_id: user.user_id,
+                    user_name: user.user_name.clone(),
+                    ipaddr,
+                    os,
+                    browser,
+                    failures: 0,
+                },
+            )
+            .await;
+        return Ok(ResponseResult::success_with_msg(
+            msg,
+            LoginVO {
+                access_token: None,
+                refresh_token: None,
+                challenge_token: Some(challenge_token),
+                challenge_type: Some(kind),
+            },
+        ));
+    }
+
+    let token_vo = issue_tokens(state, user, ipaddr.clone(), os.clone(), browser.clone()).await?;
     record_login_log(
-        db_pool,
-        username.clone(),
-        ipaddr.clone(),
+        state,
+        user.user_name.clone(),
+        ipaddr,
         os,
         browser,
         "0",
         "登录成功".to_string(),
     )
     .await;
-    Ok(ResponseResult::success_with_msg("登录成功", token_vo))
+    Ok(ResponseResult::success_with_msg(
+        "登录成功",
+        LoginVO {
+            access_token: Some(token_vo.access_token),
+            refresh_token: Some(token_vo.refresh_token),
+            challenge_token: None,
+            challenge_type: None,
+        },
+    ))
+}
+
+/// 签发令牌并记录在线会话（登录成功的最后一步）
+pub(crate) async fn issue_tokens(
+    state: &AppState,
+    user: &SysUser,
+    ipaddr: String,
+    os: Option<String>,
+    browser: Option<String>,
+) -> AppResult<TokenVO> {
+    // 生成jwt，sid 作为本次登录的会话编号
+    let sid = Uuid::new_v4().to_string();
+    let jwt_auth_util = state.jwt();
+    let acc_token = jwt_auth_util.generate_token(user.user_id, &sid, TokenType::Access)?;
+    let ref_token = jwt_auth_util.generate_token(user.user_id, &sid, TokenType::Refresh)?;
+
+    // 记录在线会话
+    let login_location = ip_location(state, &ipaddr).await;
+    state
+        .ext::<SessionCache>()?
+        .insert(SysUserOnline {
+            token_id: sid,
+            user_id: user.user_id,
+            user_name: user.user_name.clone(),
+            tick_name: user.nick_name.clone(),
+            ipaddr: Some(ipaddr),
+            login_location,
+            browser,
+            os,
+            login_time: OffsetDateTime::now_utc(),
+            token: acc_token.clone(),
+            refresh_token: ref_token.clone(),
+        })
+        .await;
+
+    Ok(TokenVO {
+        access_token: acc_token,
+        refresh_token: ref_token,
+    })
 }
 
 ///登出
 #[endpoint(tags("登录"), summary = "登出")]
-pub async fn logout(req: &mut Request) -> AppResult<ResponseResult<()>> {
-    //直接刷新token
-    let _ = ref_token(req)?;
+pub async fn logout(
+    depot: &mut Depot,
+    jwt: Jwt,
+    sessions: Ext<SessionCache>,
+) -> AppResult<ResponseResult<()>> {
+    info!("[HANDLER] Entering logout");
+    let claims = depot
+        .get::<Claims>(CLAIMS)
+        .map_err(|_| AppError::TokenInvalid)?;
+    // 吊销当前访问令牌及整个会话（含刷新令牌）
+    jwt.revoke_token(claims).await;
+    sessions.revoke(&jwt, &claims.sid).await;
     ResponseResult::success(()).into()
 }
 
+/// 从认证中间件写入的 Claims 中获取当前登录用户ID
+pub(crate) fn current_user_id(depot: &Depot) -> AppResult<i32> {
+    depot
+        .get::<Claims>(CLAIMS)
+        .map(|c| c.sub)
+        .map_err(|_| AppError::TokenInvalid)
+}
+
 ///查看当前登录用户基本信息
 #[endpoint(tags("登录"), summary = "查看当前登录用户基本信息")]
-pub async fn get_info(req: &mut Request) -> AppResult<ResponseResult<SysUserVO>> {
+pub async fn get_info(req: &mut Request, db: Db, jwt: Jwt) -> AppResult<ResponseResult<SysUserVO>> {
     info!("[HANDLER] Entering get info of current login user");
-    let jwt_auth_util = JWTTool::get()?;
-    let token = jwt_auth_util.extract_token(req)?;
+    let token = jwt.extract_token(req)?;
     dbg!(&token);
-    let ref_claims = jwt_auth_util.verify_acc_token(&token)?;
+    let ref_claims = jwt.verify_acc_token(&token)?;
 
-    let db = DBPool::get().await?;
-    let user = service::select_user_by_id(db, ref_claims.sub).await?;
+    let user = service::select_user_by_id(&db, ref_claims.sub).await?;
     //TODO: 组装角色、权限信息
     info!("[HANDLER] User info: {:?}", user);
     ResponseResult::success(user).into()
 }
 
-#[handler]
+/// 用户注册
+#[endpoint(tags("登录"), summary = "用户注册")]
 pub async fn register(
-    _username: QueryParam<String>,
-    _password: QueryParam<String>,
-) -> AppResult<ResponseResult<Value>> {
-    //TODO:
-    todo!()
+    register_dto: JsonBody<RegisterDTO>,
+    req: &mut Request,
+    state: State,
+    policy: Ext<PasswordPolicy>,
+    cap_cache: Ext<CapCache>,
+    ua_parser: Ext<Arc<UserAgentParser>>,
+) -> AppResult<ResponseResult<()>> {
+    let register_dto = register_dto.into_inner();
+    info!(
+        "[HANDLER] Entering register with username: {}",
+        register_dto.username
+    );
+    let setting = &state.config().register;
+    if !setting.enabled {
+        return Err(AppError::ValidationFailed(
+            "当前系统没有开启注册功能".to_string(),
+        ));
+    }
+    let (ipaddr, os, browser) = client_info(req, &ua_parser);
+    if !check_captcha(&cap_cache, &register_dto.captcha).await? {
+        return Err(AppError::CaptchaError);
+    }
+
+    // 需要审核时先创建为停用状态，管理员审核通过后启用
+    let (status, msg) = if setting.require_approval {
+        ("1", "注册成功，请等待管理员审核")
+    } else {
+        ("0", "注册成功")
+    };
+    service::register_user(
+        state.db(),
+        &policy,
+        &register_dto,
+        &setting.default_roles,
+        status,
+    )
+    .await?;
+    write_login_log(
+        &state,
+        register_dto.username,
+        ipaddr,
+        os,
+        browser,
+        "0",
+        msg.to_string(),
+    )
+    .await;
+    ResponseResult::success_msg(msg).into()
 }
 
-async fn record_login_log(
-    db_pool: &'static PgPool,
+/// 记录登录日志，并按结果统计登录次数
+pub(crate) async fn record_login_log(
+    state: &AppState,
     username: String,
     ipaddr: String,
     os: Option<String>,
@@ -293,12 +575,28 @@ async fn record_login_log(
     status: &'static str,
     msg: String,
 ) {
+    let result = if status == "0" { "success" } else { "failure" };
+    if let Ok(metrics) = state.ext::<MetricsTool>() {
+        LOGIN_TOTAL.inc(metrics, &[("result", result)]);
+    }
+    write_login_log(state, username, ipaddr, os, browser, status, msg).await;
+}
+
+async fn write_login_log(
+    state: &AppState,
+    username: String,
+    ipaddr: String,
+    os: Option<String>,
+    browser: Option<String>,
+    status: &'static str,
+    msg: String,
+) {
+    let login_location = ip_location(state, &ipaddr).await;
     let log = login_info::model::SysLoginInfor {
         info_id: 0,
         user_name: Some(username),
         ipaddr: Some(ipaddr),
-        // 以下字段可以后续通过 User-Agent 解析库或 IP 地址库来填充
-        login_location: None,
+        login_location,
         browser,
         os,
         status: Some(status.to_string()),
@@ -307,9 +605,17 @@ async fn record_login_log(
         login_time: Some(OffsetDateTime::now_utc()),
     };
 
-    // 在一个独立的后台任务中执行数据库写入
-    tokio::spawn(async move {
-        if let Err(e) = login_info::service::add_logininfor(db_pool, log).await {
+    // 在一个独立的后台任务中执行数据库写入，停机时等待完成
+    let background = match state.ext::<BackgroundTasks>() {
+        Ok(background) => background,
+        Err(e) => {
+            error!("[LOG_TASK] 记录登录日志失败: {:?}", e);
+            return;
+        }
+    };
+    let db_pool = state.db().clone();
+    background.spawn(LOGIN_LOG_TASK, log, |log| async move {
+        if let Err(e) = login_info::service::add_logininfor(&db_pool, log).await {
             // 这里的错误只会打印到服务器日志，不会影响主登录流程
             error!("[LOG_TASK] 记录登录日志失败: {:?}", e);
         } else {
@@ -318,6 +624,52 @@ async fn record_login_log(
     });
 }
 
+/// 重新写入上次停机时未完成的操作日志和登录日志，写库失败的保留到下次启动
+pub async fn replay_audit_spill(db: &PgPool, spill_path: &str) {
+    let spill_path = Path::new(spill_path);
+    let tasks = match BackgroundTasks::read_spilled(spill_path) {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            error!("[HANDLER] replay audit spill error: {:?}", e);
+            return;
+        }
+    };
+    if tasks.is_empty() {
+        return;
+    }
+    let mut failed = Vec::new();
+    for task in tasks {
+        let result = match task.kind.as_str() {
+            OPER_LOG_TASK => match serde_json::from_value(task.payload.clone()) {
+                Ok(log) => operlog::service::add(db, log).await,
+                Err(e) => {
+                    // 内容无法解析，重试也不会成功，直接丢弃
+                    error!("[HANDLER] invalid spilled {}: {:?}", task.kind, e);
+                    continue;
+                }
+            },
+            LOGIN_LOG_TASK => match serde_json::from_value(task.payload.clone()) {
+                Ok(log) => login_info::service::add_logininfor(db, log).await,
+                Err(e) => {
+                    error!("[HANDLER] invalid spilled {}: {:?}", task.kind, e);
+                    continue;
+                }
+            },
+            kind => {
+                warn!("[HANDLER] Unknown spilled task kind: {}", kind);
+                continue;
+            }
+        };
+        if let Err(e) = result {
+            error!("[HANDLER] replay {} error: {:?}", task.kind, e);
+            failed.push(task);
+        }
+    }
+    if let Err(e) = BackgroundTasks::finish_spilled(spill_path, &failed) {
+        error!("[HANDLER] replay audit spill error: {:?}", e);
+    }
+}
+
 //日志中间件， 由于会调用业务方法， 不能放到framework  namespace中。否则会循环依赖
 #[handler]
 pub async fn oper_log_middleware(
@@ -331,7 +683,7 @@ pub async fn oper_log_middleware(
     let method = req.method().to_string();
     let uri = req.uri().to_string();
     // 获取 IP，需要 Server 配置过 socket 地址
-    let oper_ip = req.remote_addr().to_string();
+    let oper_ip = client_ip(req);
 
     // 获取请求参数
     // 注意：req.payload().await 会读取 Body 并缓存，
@@ -346,7 +698,7 @@ pub async fn oper_log_middleware(
     } else {
         // 普通 JSON 请求才去读 payload
         match req.payload().await {
-            Ok(bytes) => String::from_utf8_lossy(bytes).to_string(),
+            Ok(bytes) => mask_password(&String::from_utf8_lossy(bytes)),
             Err(_) => "".to_string(),
         }
     };
@@ -375,12 +727,13 @@ pub async fn oper_log_middleware(
         } else {
             1
         };
-        let db = DBPool::get().await?;
+        let state = AppState::from_depot(depot)?.clone();
+        let db = state.db().clone();
 
         let claims = depot.get::<Claims>(CLAIMS).ok();
         let user = match claims {
             Some(c) => {
-                let user = user::service::select_user_by_id(db, c.sub).await?;
+                let user = user::service::select_user_by_id(&db, c.sub).await?;
                 Some(user)
             }
             None => None,
@@ -394,53 +747,55 @@ pub async fn oper_log_middleware(
             oper_name: user.as_ref().map(|u| u.user_name.to_string()),
             oper_nick_name: user.map(|u| u.nick_name),
             oper_url: Some(uri),
+            oper_location: ip_location(&state, &oper_ip).await,
             oper_ip: Some(oper_ip),
-            oper_location: None,
             oper_param: Some(oper_param),
             json_result: Some(json_result),
             status: Some(status),
             error_msg: None,
             oper_time: Some(OffsetDateTime::now_utc()),
             cost_time: Some(cost_time),
+            request_id: depot.get::<String>(REQUEST_ID).ok().cloned(),
         };
 
-        //  异步写入数据库，不阻塞当前请求返回
-        tokio::spawn(async move {
-            operlog::service::add(db, log)
-                .await
-                .map_err(|e| error!("[HANDLER] operlog::add error: {:?}", e))
-        });
+        //  异步写入数据库，不阻塞当前请求返回，停机时等待完成
+        let metrics = state.ext::<MetricsTool>()?.clone();
+        OPER_LOG_PENDING.add(&metrics, &[], 1.0);
+        state
+            .ext::<BackgroundTasks>()?
+            .spawn(OPER_LOG_TASK, log, |log| async move {
+                if let Err(e) = operlog::service::add(&db, log).await {
+                    error!("[HANDLER] operlog::add error: {:?}", e);
+                }
+                OPER_LOG_PENDING.add(&metrics, &[], -1.0);
+            });
     }
     Ok(())
 }
 
-// 日志元数据，存放在 Depot 中
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LogMeta {
-    pub title: String,       // 模块标题，如 "文章管理"
-    pub business_type: i16,  // 1:新增, 2:修改, 3:删除, etc.
-    pub method_name: String, // 方法名称
-}
-
-const LOG_META_KEY: &str = "sys_oper_log_meta";
-
-impl LogMeta {
-    // 辅助函数：在 Handler 中调用此方法设置日志信息
-    pub fn set(depot: &mut Depot, title: &str, business_type: i16, method_name: &str) {
-        depot.insert(
-            LOG_META_KEY,
-            LogMeta {
-                title: title.to_string(),
-                business_type,
-                method_name: method_name.to_string(),
-            },
-        );
+/// 隐去请求参数中名称包含 password 的字段，非 JSON 参数原样返回
+fn mask_password(param: &str) -> String {
+    fn mask(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if key.to_lowercase().contains("password") {
+                        *value = serde_json::Value::String("******".to_string());
+                    } else {
+                        mask(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => values.iter_mut().for_each(mask),
+            _ => {}
+        }
     }
-
-    // 辅助函数：在 Middleware 中取出日志信息
-    pub fn get(depot: &Depot) -> Option<&LogMeta> {
-        // 使用 .ok() 将 Result 转换为 Option
-        depot.get::<LogMeta>(LOG_META_KEY).ok()
+    match serde_json::from_str::<serde_json::Value>(param) {
+        Ok(mut value) => {
+            mask(&mut value);
+            value.to_string()
+        }
+        Err(_) => param.to_string(),
     }
 }
 
@@ -455,7 +810,17 @@ mod test {
     };
     use user_agent_parser::UserAgentParser;
 
-    use crate::handle::PASER;
+    use super::mask_password;
+
+    #[test]
+    fn mask_password_test() {
+        assert_eq!(
+            mask_password(r#"{"userId":2,"password":"secret","items":[{"oldPassword":"x"}]}"#),
+            r#"{"items":[{"oldPassword":"******"}],"password":"******","userId":2}"#
+        );
+        assert_eq!(mask_password("user_id=2"), "user_id=2");
+    }
+
     #[test]
     fn argon2_test() -> password_hash::Result<()> {
         let password = b"admin"; // Bad password; don't actually use!
@@ -482,8 +847,7 @@ mod test {
     }
     #[test]
     fn user_agent_parser() {
-        let user_agent_parser =
-            PASER.get_or_init(|| UserAgentParser::from_path("../../regexes.yaml").expect(""));
+        let user_agent_parser = UserAgentParser::from_path("../../regexes.yaml").expect("");
         let s = "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:142.0) Gecko/20100101 Firefox/142.0";
         let os = user_agent_parser
             .parse_os(s)
@@ -1,16 +1,52 @@
-use framework::midddleware::auth;
+use std::sync::Arc;
+
+use common::{AppError, AppResult};
+use framework::{
+    api_key::ApiKeyTool,
+    metrics::MetricsTool,
+    midddleware::{auth, interactive_only},
+    permission::PermTool,
+    state::AppState,
+};
 use salvo::Router;
+use user_agent_parser::UserAgentParser;
 
-use crate::handle::{get_captcha_image, get_info, login, logout, refresh_token_handler};
+use crate::api_key::service::SysApiKeyStore;
+use crate::handle::{
+    get_captcha_image, get_info, login, login_change_password, logout, refresh_token_handler,
+    register,
+};
+use crate::model::{CapCache, ChallengeCache};
+use crate::user::service::UserPermissionLoader;
 
+pub mod api_key;
+pub mod dept;
 pub mod dict;
 pub mod file;
 pub mod handle;
 pub mod menu;
+pub mod mfa;
 pub mod model;
+pub mod post;
 pub mod role;
+pub mod sso;
 pub mod user;
 
+/// 添加本模块的应用状态：验证码缓存、登录挑战缓存、User-Agent 解析器、权限工具、API Key 工具，
+/// 需在 [`framework::init_state`] 之后调用
+pub fn init_state(state: AppState) -> AppResult<AppState> {
+    let user_agent_parser = UserAgentParser::from_path("regexes.yaml")
+        .map_err(|e| AppError::Other(format!("加载 regexes.yaml 失败: {e:?}")))?;
+    let db = state.db().clone();
+    let metrics = state.ext::<MetricsTool>()?.clone();
+    Ok(state
+        .with(CapCache::new(metrics))
+        .with(ChallengeCache::new())
+        .with(Arc::new(user_agent_parser))
+        .with(PermTool::new(UserPermissionLoader::new(db.clone())))
+        .with(ApiKeyTool::new(SysApiKeyStore::new(db))))
+}
+
 pub fn init_router() -> Router {
     Router::new()
         .path("sys")
@@ -20,16 +56,37 @@ pub fn init_router() -> Router {
                 //获取验证码
                 .push(Router::with_path("catpcha").get(get_captcha_image))
                 //登录
-                .push(Router::with_path("login").post(login)),
+                .push(Router::with_path("login").post(login))
+                //用户注册
+                .push(Router::with_path("register").post(register))
+                //登录时修改过期密码
+                .push(Router::with_path("login/password").post(login_change_password))
+                //双因素认证（登录第二步）
+                .push(mfa::init_login_router())
+                //单点登录
+                .push(sso::init_login_router()),
         )
         .push(
             Router::new()
                 //需要认证的路由
                 .hoop(auth)
-                .push(Router::with_path("logout").post(logout))
-                //刷新token
-                .push(Router::with_path("refresh_token").post(refresh_token_handler))
-                .push(Router::with_path("login_info").post(get_info))
+                .push(
+                    Router::new()
+                        //仅限登录会话，API Key 不能访问
+                        .hoop(interactive_only)
+                        .push(Router::with_path("logout").post(logout))
+                        //刷新token
+                        .push(Router::with_path("refresh_token").post(refresh_token_handler))
+                        .push(Router::with_path("login_info").post(get_info))
+                        //双因素认证
+                        .push(mfa::init_router())
+                        //绑定外部账号
+                        .push(sso::init_router())
+                        //API Key
+                        .push(api_key::init_router())
+                        //个人中心
+                        .push(user::init_profile_router()),
+                )
                 //用户相关router
                 .push(user::init_router())
                 //枚举相关接口
@@ -37,7 +94,11 @@ pub fn init_router() -> Router {
                 //菜单相关
                 .push(menu::init_router())
                 //角色相关
-                .push(role::init_router()),
+                .push(role::init_router())
+                //部门相关
+                .push(dept::init_router())
+                //岗位相关
+                .push(post::init_router()),
         )
         .push(
             Router::new()
@@ -45,3 +106,89 @@ pub fn init_router() -> Router {
                 .push(file::init_router()),
         )
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, time::Duration};
+
+    use async_trait::async_trait;
+    use common::{AppResult, constants::Permission};
+    use config::{Config, File, FileFormat};
+    use framework::{
+        Setting,
+        api_key::{API_KEY_HEADER, ApiKeyRecord, ApiKeyStore, ApiKeyTool},
+        jwt::{JwtAuthUtil, JwtConfig},
+        state::{AppState, inject},
+    };
+    use salvo::{Router, Service, http::StatusCode, test::TestClient};
+    use sqlx::postgres::PgPoolOptions;
+
+    struct FakeStore(ApiKeyRecord);
+
+    #[async_trait]
+    impl ApiKeyStore for FakeStore {
+        async fn find(&self, _prefix: &str) -> AppResult<Option<ApiKeyRecord>> {
+            Ok(Some(self.0.clone()))
+        }
+        async fn touch(&self, _key_id: i32) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn interactive_only_test() -> anyhow::Result<()> {
+        let key = ApiKeyTool::generate()?;
+        let api_key_tool = ApiKeyTool::new(FakeStore(ApiKeyRecord {
+            key_id: 1,
+            user_id: 1,
+            key_hash: key.key_hash,
+            scopes: HashSet::from([Permission::UserList.as_ref().to_string()]),
+            expire_time: None,
+        }));
+        // 数据库不可用，请求到达处理函数时返回 500
+        let db = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("postgres://postgres@127.0.0.1:1/test")?;
+        let jwt = JwtAuthUtil::new(JwtConfig::new(
+            "secret".to_string(),
+            5,
+            10,
+            "test".to_string(),
+        ));
+        let setting: Setting = Config::builder()
+            .add_source(File::from_str(
+                include_str!("../../../config/test.toml"),
+                FileFormat::Toml,
+            ))
+            .build()?
+            .try_deserialize()?;
+        let state = AppState::new(db, jwt, setting).with(api_key_tool);
+        let service = Service::new(Router::new().hoop(inject(state)).push(super::init_router()));
+
+        // 本人操作及未声明权限的接口拒绝 API Key
+        for path in [
+            "user/profile/password",
+            "user/profile/update",
+            "mfa/disable",
+            "file/upload",
+        ] {
+            let res = TestClient::post(format!("http://127.0.0.1/sys/{path}"))
+                .add_header(API_KEY_HEADER, &key.key, true)
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN), "{path}");
+        }
+        for path in [
+            "dict/data/list_by_type?dict_type=x",
+            "menu/menu_tree",
+            "file/index",
+        ] {
+            let res = TestClient::get(format!("http://127.0.0.1/sys/{path}"))
+                .add_header(API_KEY_HEADER, &key.key, true)
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN), "{path}");
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use common::{AppError, AppResult, response::ResponseResult};
 use framework::{
-    db::DBPool,
     jwt::{CLAIMS, Claims},
+    permission::PermTool,
+    state::{Db, Ext},
 };
 use salvo::{
     Depot, handler,
@@ -20,32 +21,36 @@ use salvo::Writer;
 
 ///新增菜单
 #[endpoint(tags("菜单管理"), summary = "添加菜单")]
-pub async fn add(menu: JsonBody<MenuDTO>) -> AppResult<ResponseResult<()>> {
+pub async fn add(menu: JsonBody<MenuDTO>, db: Db) -> AppResult<ResponseResult<()>> {
     let menu = menu.into_inner();
     info!("[HANDLER] Entering menu::insert with body: {:?}", menu);
-
-    let db = DBPool::get().await?;
-    service::add_menu(db, menu).await?;
+    service::add_menu(&db, menu).await?;
     ResponseResult::success_msg("新增成功").into()
 }
 
 ///修改菜单
 #[endpoint(tags("菜单管理"), summary = "修改菜单")]
-pub async fn update(menu: JsonBody<MenuDTO>) -> AppResult<ResponseResult<()>> {
+pub async fn update(
+    menu: JsonBody<MenuDTO>,
+    db: Db,
+    perm_tool: Ext<PermTool>,
+) -> AppResult<ResponseResult<()>> {
     let menu = menu.into_inner();
     info!("[HANDLER] Entering menu::insert with body: {:?}", menu);
     if menu.menu_id.is_none() {
         return Err(AppError::Other("菜单ID不能为空".to_string()));
     }
-
-    let db = DBPool::get().await?;
-    service::update_menu(db, menu).await?;
+    service::update_menu(&db, menu).await?;
+    perm_tool.invalidate_all();
     ResponseResult::success_msg("修改成功").into()
 }
 
 /// 获取菜单树（根据权限）
 #[handler]
-pub async fn get_menu_tree(depot: &mut Depot) -> AppResult<ResponseResult<Vec<MenuTreeVo>>> {
+pub async fn get_menu_tree(
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<Vec<MenuTreeVo>>> {
     let user_id = depot
         .get::<Claims>(CLAIMS)
         .map(|s| &s.sub)
@@ -54,17 +59,16 @@ pub async fn get_menu_tree(depot: &mut Depot) -> AppResult<ResponseResult<Vec<Me
         "[HANDLER] Entering get menu tree  with user id in claims {:?}",
         user_id
     );
-    let db = DBPool::get().await?;
-    let menu_tree = service::select_menu_tree_by_user_id(db, *user_id).await?;
+    let menu_tree = service::select_menu_tree_by_user_id(&db, *user_id).await?;
 
     ResponseResult::success(menu_tree).into()
 }
 
 ///获取菜单列表
 #[handler]
-pub async fn list() -> AppResult<ResponseResult<Vec<MenuTreeVo>>> {
+pub async fn list(db: Db) -> AppResult<ResponseResult<Vec<MenuTreeVo>>> {
     info!("[HANDLER] Entering get menu tree  of all  menus");
-    let menus = service::select_all_menu_list(DBPool::get().await?).await?;
+    let menus = service::select_all_menu_list(&db).await?;
     let menu_tree = MenuTreeVo::build_menu_tree(menus);
     ResponseResult::success(menu_tree).into()
 }
@@ -80,21 +84,26 @@ pub async fn list() -> AppResult<ResponseResult<Vec<MenuTreeVo>>> {
     //     (status_code = 500, description = "Internal server error")
     // )
 )]
-pub async fn get_detail(menu_id: PathParam<i32>) -> AppResult<ResponseResult<SysMenu>> {
-    let menu = service::select_menu_by_id(DBPool::get().await?, menu_id.into_inner()).await?;
+pub async fn get_detail(menu_id: PathParam<i32>, db: Db) -> AppResult<ResponseResult<SysMenu>> {
+    let menu = service::select_menu_by_id(&db, menu_id.into_inner()).await?;
     ResponseResult::success(menu).into()
 }
 
 //删除菜单
 #[endpoint(tags("菜单管理"), summary = "删除菜单")]
-pub async fn delete(menu_id: PathParam<i32>) -> AppResult<ResponseResult<()>> {
-    service::delete_menu_by_id(DBPool::get().await?, menu_id.into_inner()).await?;
+pub async fn delete(
+    menu_id: PathParam<i32>,
+    db: Db,
+    perm_tool: Ext<PermTool>,
+) -> AppResult<ResponseResult<()>> {
+    service::delete_menu_by_id(&db, menu_id.into_inner()).await?;
+    perm_tool.invalidate_all();
     ResponseResult::success_msg("删除成功").into()
 }
 
 ///FIX:  完善功能(不确定是否需要)
 #[handler]
-pub async fn get_routers(depot: &mut Depot) -> AppResult<ResponseResult<Vec<RouterVo>>> {
+pub async fn get_routers(depot: &mut Depot, db: Db) -> AppResult<ResponseResult<Vec<RouterVo>>> {
     let user_id = depot
         .get::<Claims>(CLAIMS)
         .map(|s| &s.sub)
@@ -103,8 +112,7 @@ pub async fn get_routers(depot: &mut Depot) -> AppResult<ResponseResult<Vec<Rout
         "[HANDLER] Entering get routers  with user id in claims {:?}",
         user_id
     );
-    let db = DBPool::get().await?;
-    let menu_tree = service::select_menu_tree_by_user_id(db, *user_id).await?;
+    let menu_tree = service::select_menu_tree_by_user_id(&db, *user_id).await?;
 
     let router_tree = RouterVo::build_from_menu_tree(menu_tree);
     ResponseResult::success(router_tree).into()
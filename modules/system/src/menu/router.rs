@@ -1,3 +1,5 @@
+use common::constants::Permission;
+use framework::midddleware::{interactive_only, require_perm};
 use salvo::Router;
 
 use crate::menu::handle::{add, delete, get_detail, get_menu_tree, list, update};
@@ -5,10 +7,35 @@ use crate::menu::handle::{add, delete, get_detail, get_menu_tree, list, update};
 pub fn init_router() -> Router {
     Router::new()
         .path("menu")
-        .push(Router::with_path("add").post(add))
-        .push(Router::with_path("update").put(update))
-        .push(Router::with_path("delete/{menu_id}").delete(delete))
-        .push(Router::with_path("list").get(list))
-        .push(Router::with_path("{menu_id}").get(get_detail))
-        .push(Router::with_path("menu_tree").get(get_menu_tree))
+        .push(
+            Router::with_path("add")
+                .hoop(require_perm(Permission::MenuAdd))
+                .post(add),
+        )
+        .push(
+            Router::with_path("update")
+                .hoop(require_perm(Permission::MenuEdit))
+                .put(update),
+        )
+        .push(
+            Router::with_path("delete/{menu_id}")
+                .hoop(require_perm(Permission::MenuRemove))
+                .delete(delete),
+        )
+        .push(
+            Router::with_path("list")
+                .hoop(require_perm(Permission::MenuList))
+                .get(list),
+        )
+        //当前用户的菜单树，无需额外权限（API Key 不能访问）
+        .push(
+            Router::with_path("menu_tree")
+                .hoop(interactive_only)
+                .get(get_menu_tree),
+        )
+        .push(
+            Router::with_path("{menu_id}")
+                .hoop(require_perm(Permission::MenuQuery))
+                .get(get_detail),
+        )
 }
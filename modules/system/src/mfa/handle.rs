@@ -0,0 +1,217 @@
+use common::{AppError, AppResult, response::ResponseResult};
+use framework::{
+    login_lock::LoginLockTool,
+    state::{AppState, Db, Ext, Jwt, State},
+};
+use salvo::oapi::endpoint;
+use salvo::oapi::extract::JsonBody;
+use salvo::{Depot, Writer};
+use tracing::{info, warn};
+
+use crate::handle::{current_user_id, issue_tokens, record_login_log};
+use crate::mfa::model::{MfaChallengeDTO, MfaCodeDTO, MfaConfirmVO, MfaSetupVO, MfaVerifyDTO};
+use crate::mfa::service;
+use crate::model::{
+    ChallengeCache, ChallengeKind, LoginChallenge, MAX_CHALLENGE_FAILURES, TokenVO,
+};
+use crate::user;
+
+/// 登录第二步：校验动态口令或恢复码，通过后签发令牌
+#[endpoint(tags("双因素认证"), summary = "登录校验动态口令")]
+pub async fn login_verify(
+    dto: JsonBody<MfaVerifyDTO>,
+    state: State,
+    challenges: Ext<ChallengeCache>,
+) -> AppResult<ResponseResult<TokenVO>> {
+    info!("[HANDLER] Entering mfa::login_verify");
+    let MfaVerifyDTO {
+        challenge_token,
+        code,
+    } = dto.into_inner();
+    let challenge = challenges
+        .get(&challenge_token, ChallengeKind::Verify)
+        .await?;
+    check_lock(&state, &challenge).await?;
+    let db = state.db();
+    let user = user::service::select_user_entity(db, challenge.user_id).await?;
+    if !service::verify_code(db, &user, &code).await? {
+        return Err(challenge_failed(&state, &challenges, &challenge_token, challenge).await);
+    }
+    challenges.remove(&challenge_token).await;
+    let token_vo = complete_login(&state, challenge, &user).await?;
+    Ok(ResponseResult::success_with_msg("登录成功", token_vo))
+}
+
+/// 登录过程中强制绑定：获取绑定信息
+#[endpoint(tags("双因素认证"), summary = "登录时获取绑定信息")]
+pub async fn login_setup(
+    dto: JsonBody<MfaChallengeDTO>,
+    db: Db,
+    jwt: Jwt,
+    challenges: Ext<ChallengeCache>,
+) -> AppResult<ResponseResult<MfaSetupVO>> {
+    info!("[HANDLER] Entering mfa::login_setup");
+    let challenge = challenges
+        .get(&dto.challenge_token, ChallengeKind::Enroll)
+        .await?;
+    let user = user::service::select_user_entity(&db, challenge.user_id).await?;
+    ResponseResult::success(service::setup(&db, &user, jwt.issuer()).await?).into()
+}
+
+/// 登录过程中强制绑定：确认绑定，返回恢复码并签发令牌
+#[endpoint(tags("双因素认证"), summary = "登录时确认绑定")]
+pub async fn login_confirm(
+    dto: JsonBody<MfaVerifyDTO>,
+    state: State,
+    challenges: Ext<ChallengeCache>,
+) -> AppResult<ResponseResult<MfaConfirmVO>> {
+    info!("[HANDLER] Entering mfa::login_confirm");
+    let MfaVerifyDTO {
+        challenge_token,
+        code,
+    } = dto.into_inner();
+    let challenge = challenges
+        .get(&challenge_token, ChallengeKind::Enroll)
+        .await?;
+    check_lock(&state, &challenge).await?;
+    let db = state.db();
+    let user = user::service::select_user_entity(db, challenge.user_id).await?;
+    let recovery_codes = match service::confirm(db, &user, &code).await {
+        Ok(codes) => codes,
+        Err(AppError::ValidationFailed(_)) => {
+            return Err(challenge_failed(&state, &challenges, &challenge_token, challenge).await);
+        }
+        Err(e) => return Err(e),
+    };
+    challenges.remove(&challenge_token).await;
+    let token_vo = complete_login(&state, challenge, &user).await?;
+    ResponseResult::success(MfaConfirmVO {
+        recovery_codes,
+        access_token: Some(token_vo.access_token),
+        refresh_token: Some(token_vo.refresh_token),
+    })
+    .into()
+}
+
+/// 已登录用户：获取绑定信息
+#[endpoint(tags("双因素认证"), summary = "获取绑定信息")]
+pub async fn setup(depot: &mut Depot, db: Db, jwt: Jwt) -> AppResult<ResponseResult<MfaSetupVO>> {
+    let user_id = current_user_id(depot)?;
+    info!("[HANDLER] Entering mfa::setup with user_id: {}", user_id);
+    let user = user::service::select_user_entity(&db, user_id).await?;
+    ResponseResult::success(service::setup(&db, &user, jwt.issuer()).await?).into()
+}
+
+/// 已登录用户：确认绑定，返回恢复码
+#[endpoint(tags("双因素认证"), summary = "确认绑定")]
+pub async fn confirm(
+    dto: JsonBody<MfaCodeDTO>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<MfaConfirmVO>> {
+    let user_id = current_user_id(depot)?;
+    info!("[HANDLER] Entering mfa::confirm with user_id: {}", user_id);
+    let user = user::service::select_user_entity(&db, user_id).await?;
+    let recovery_codes = service::confirm(&db, &user, &dto.code).await?;
+    ResponseResult::success(MfaConfirmVO {
+        recovery_codes,
+        access_token: None,
+        refresh_token: None,
+    })
+    .into()
+}
+
+/// 已登录用户：关闭双因素认证（所属角色强制要求时不允许关闭）
+#[endpoint(tags("双因素认证"), summary = "关闭双因素认证")]
+pub async fn disable(
+    dto: JsonBody<MfaCodeDTO>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let user_id = current_user_id(depot)?;
+    info!("[HANDLER] Entering mfa::disable with user_id: {}", user_id);
+    if service::is_mfa_required(&db, user_id).await? {
+        return Err(AppError::ValidationFailed(
+            "所属角色要求启用双因素认证，不能关闭".to_string(),
+        ));
+    }
+    let user = user::service::select_user_entity(&db, user_id).await?;
+    if !service::verify_code(&db, &user, &dto.code).await? {
+        return Err(AppError::ValidationFailed("动态口令错误".to_string()));
+    }
+    service::disable(&db, user_id).await?;
+    ResponseResult::success_msg("已关闭双因素认证").into()
+}
+
+/// 第二步同样受登录锁定约束，锁定期间不再校验动态口令
+async fn check_lock(state: &AppState, challenge: &LoginChallenge) -> AppResult<()> {
+    let result = state
+        .ext::<LoginLockTool>()?
+        .check(&challenge.user_name, &challenge.ipaddr)
+        .await;
+    if result.is_err() {
+        record_login_log(
+            state,
+            challenge.user_name.clone(),
+            challenge.ipaddr.clone(),
+            challenge.os.clone(),
+            challenge.browser.clone(),
+            "1",
+            "登录失败次数过多，账户已锁定".to_string(),
+        )
+        .await;
+    }
+    result
+}
+
+/// 记录第二步失败：计入登录锁定，超过次数后挑战作废
+async fn challenge_failed(
+    state: &AppState,
+    challenges: &ChallengeCache,
+    challenge_token: &str,
+    mut challenge: LoginChallenge,
+) -> AppError {
+    if let Ok(login_lock) = state.ext::<LoginLockTool>() {
+        login_lock
+            .record_failure(&challenge.user_name, &challenge.ipaddr)
+            .await;
+    }
+    challenge.failures += 1;
+    if challenge.failures >= MAX_CHALLENGE_FAILURES {
+        warn!(
+            "[HANDLER] MFA challenge of user '{}' discarded after {} failures",
+            challenge.user_name, challenge.failures
+        );
+        challenges.remove(challenge_token).await;
+    } else {
+        challenges.insert(challenge_token, challenge).await;
+    }
+    AppError::ValidationFailed("动态口令错误".to_string())
+}
+
+/// 第二步通过：签发令牌并记录登录日志
+async fn complete_login(
+    state: &AppState,
+    challenge: LoginChallenge,
+    user: &crate::user::model::SysUser,
+) -> AppResult<TokenVO> {
+    let LoginChallenge {
+        user_name,
+        ipaddr,
+        os,
+        browser,
+        ..
+    } = challenge;
+    let token_vo = issue_tokens(state, user, ipaddr.clone(), os.clone(), browser.clone()).await?;
+    record_login_log(
+        state,
+        user_name,
+        ipaddr,
+        os,
+        browser,
+        "0",
+        "登录成功".to_string(),
+    )
+    .await;
+    Ok(token_vo)
+}
@@ -0,0 +1,7 @@
+pub mod handle;
+pub mod model;
+pub mod router;
+pub mod service;
+pub mod totp;
+
+pub use router::{init_login_router, init_router};
@@ -0,0 +1,44 @@
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+
+/// 登录第二步：校验动态口令或恢复码
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaVerifyDTO {
+    pub challenge_token: String,
+    /// 6 位动态口令或恢复码
+    pub code: String,
+}
+
+/// 登录过程中强制绑定：获取绑定信息
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallengeDTO {
+    pub challenge_token: String,
+}
+
+/// 已登录用户确认绑定/解绑时提交的动态口令
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaCodeDTO {
+    pub code: String,
+}
+
+/// 绑定信息，前端可将 otpauth URI 渲染为二维码
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaSetupVO {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// 绑定成功：恢复码仅在此时明文返回一次；登录过程中绑定时同时返回令牌
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaConfirmVO {
+    pub recovery_codes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
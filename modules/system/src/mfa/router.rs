@@ -0,0 +1,21 @@
+use salvo::Router;
+
+use crate::mfa::handle;
+
+/// 登录第二步（无需认证，凭挑战令牌访问）
+pub fn init_login_router() -> Router {
+    Router::new()
+        .path("login/mfa")
+        .push(Router::with_path("verify").post(handle::login_verify))
+        .push(Router::with_path("setup").post(handle::login_setup))
+        .push(Router::with_path("confirm").post(handle::login_confirm))
+}
+
+/// 已登录用户管理自己的双因素认证
+pub fn init_router() -> Router {
+    Router::new()
+        .path("mfa")
+        .push(Router::with_path("setup").post(handle::setup))
+        .push(Router::with_path("confirm").post(handle::confirm))
+        .push(Router::with_path("disable").post(handle::disable))
+}
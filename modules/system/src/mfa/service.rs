@@ -0,0 +1,219 @@
+use argon2::{
+    Argon2, PasswordHash, PasswordVerifier,
+    password_hash::rand_core::{OsRng, RngCore},
+};
+use common::{AppError, AppResult};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::mfa::{model::MfaSetupVO, totp};
+use crate::user::{model::SysUser, service::hash_password};
+
+/// 每次生成的恢复码数量
+const RECOVERY_CODE_COUNT: usize = 10;
+/// 恢复码字符集（去除易混淆的 0/o/1/l/i）
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// 用户所属角色是否强制要求双因素认证
+pub(crate) async fn is_mfa_required(db: &PgPool, user_id: i32) -> AppResult<bool> {
+    let required = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM sys_role sr
+            INNER JOIN sys_user_role sur ON sr.role_id = sur.role_id
+            WHERE sur.user_id = $1 AND sr.force_mfa = '1' AND sr.status = '0' AND sr.del_flag = '0'
+        ) AS "required!""#,
+        user_id
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(required)
+}
+
+pub(crate) fn is_enabled(user: &SysUser) -> bool {
+    user.totp_enabled.as_deref() == Some("1")
+}
+
+/// 生成待确认的 TOTP 密钥，确认前不生效
+pub(crate) async fn setup(db: &PgPool, user: &SysUser, issuer: &str) -> AppResult<MfaSetupVO> {
+    info!(
+        "[SERVICE] Entering mfa::setup with user_id: {}",
+        user.user_id
+    );
+    if is_enabled(user) {
+        return Err(AppError::ValidationFailed("已启用双因素认证".to_string()));
+    }
+    let secret = totp::generate_secret();
+    sqlx::query!(
+        "UPDATE sys_user SET totp_secret = $1, update_time = NOW() WHERE user_id = $2",
+        secret,
+        user.user_id
+    )
+    .execute(db)
+    .await?;
+    let otpauth_uri = totp::otpauth_uri(&secret, issuer, &user.user_name);
+    Ok(MfaSetupVO {
+        secret,
+        otpauth_uri,
+    })
+}
+
+/// 校验动态口令并启用双因素认证，返回新生成的恢复码（明文仅返回这一次）
+pub(crate) async fn confirm(db: &PgPool, user: &SysUser, code: &str) -> AppResult<Vec<String>> {
+    info!(
+        "[SERVICE] Entering mfa::confirm with user_id: {}",
+        user.user_id
+    );
+    if is_enabled(user) {
+        return Err(AppError::ValidationFailed("已启用双因素认证".to_string()));
+    }
+    let secret = user
+        .totp_secret
+        .as_deref()
+        .ok_or(AppError::ValidationFailed("请先获取绑定信息".to_string()))?;
+    let Some(step) = totp::verify(secret, code) else {
+        return Err(AppError::ValidationFailed("动态口令错误".to_string()));
+    };
+
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect();
+    let hashes = codes
+        .iter()
+        .map(|code| hash_password(&normalize_recovery_code(code)))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let mut tx = db.begin().await?;
+    sqlx::query!(
+        "UPDATE sys_user SET totp_enabled = '1', totp_last_step = $1, update_time = NOW() WHERE user_id = $2",
+        step,
+        user.user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "DELETE FROM sys_user_recovery_code WHERE user_id = $1",
+        user.user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "INSERT INTO sys_user_recovery_code (user_id, code_hash) SELECT $1, * FROM UNNEST($2::varchar[])",
+        user.user_id,
+        &hashes
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    info!(
+        "[SERVICE] MFA enabled for user_id: {}, {} recovery codes generated.",
+        user.user_id,
+        codes.len()
+    );
+    Ok(codes)
+}
+
+/// 校验动态口令，或使用一个未使用过的恢复码
+///
+/// 动态口令的时间步必须大于上次使用的时间步，同一口令在有效期内不能重放
+pub(crate) async fn verify_code(db: &PgPool, user: &SysUser, code: &str) -> AppResult<bool> {
+    let Some(secret) = user.totp_secret.as_deref().filter(|_| is_enabled(user)) else {
+        return Ok(false);
+    };
+    if let Some(step) = totp::verify(secret, code) {
+        let accepted = sqlx::query!(
+            "UPDATE sys_user SET totp_last_step = $1
+             WHERE user_id = $2 AND (totp_last_step IS NULL OR totp_last_step < $1)",
+            step,
+            user.user_id
+        )
+        .execute(db)
+        .await?
+        .rows_affected()
+            == 1;
+        if !accepted {
+            warn!(
+                "[SERVICE] Replayed TOTP step {} rejected for user_id: {}",
+                step, user.user_id
+            );
+        }
+        return Ok(accepted);
+    }
+
+    let code = normalize_recovery_code(code);
+    let recovery_codes = sqlx::query!(
+        "SELECT code_id, code_hash FROM sys_user_recovery_code WHERE user_id = $1 AND used_time IS NULL",
+        user.user_id
+    )
+    .fetch_all(db)
+    .await?;
+    for recovery_code in recovery_codes {
+        let matched = PasswordHash::new(&recovery_code.code_hash).is_ok_and(|hash| {
+            Argon2::default()
+                .verify_password(code.as_bytes(), &hash)
+                .is_ok()
+        });
+        if matched {
+            // 并发请求使用同一恢复码时，只有一个能标记成功
+            let consumed = sqlx::query!(
+                "UPDATE sys_user_recovery_code SET used_time = NOW() WHERE code_id = $1 AND used_time IS NULL",
+                recovery_code.code_id
+            )
+            .execute(db)
+            .await?
+            .rows_affected()
+                == 1;
+            if !consumed {
+                warn!(
+                    "[SERVICE] Recovery code {} already used by user_id: {}",
+                    recovery_code.code_id, user.user_id
+                );
+                return Ok(false);
+            }
+            info!(
+                "[SERVICE] Recovery code {} used by user_id: {}",
+                recovery_code.code_id, user.user_id
+            );
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 关闭双因素认证，清除密钥与恢复码
+pub(crate) async fn disable(db: &PgPool, user_id: i32) -> AppResult<()> {
+    info!("[SERVICE] Entering mfa::disable with user_id: {}", user_id);
+    let mut tx = db.begin().await?;
+    sqlx::query!(
+        "UPDATE sys_user SET totp_secret = NULL, totp_enabled = '0', update_time = NOW() WHERE user_id = $1",
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "DELETE FROM sys_user_recovery_code WHERE user_id = $1",
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 生成形如 `abcde-fghjk` 的恢复码
+fn generate_recovery_code() -> String {
+    let mut bytes = [0u8; 10];
+    OsRng.fill_bytes(&mut bytes);
+    let chars: String = bytes
+        .iter()
+        .map(|b| RECOVERY_CODE_ALPHABET[*b as usize % RECOVERY_CODE_ALPHABET.len()] as char)
+        .collect();
+    format!("{}-{}", &chars[..5], &chars[5..])
+}
+
+/// 恢复码统一去除分隔符并转为小写后再哈希/比对
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
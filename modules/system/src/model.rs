@@ -1,10 +1,10 @@
-use std::{sync::OnceLock, time::Duration};
+use std::time::Duration;
 
 use common::{AppError, AppResult};
+use framework::metrics::{Counter, MetricsTool};
 use moka::future::Cache;
 use salvo::oapi::ToSchema;
 use serde::{Deserialize, Serialize};
-use user_agent_parser::UserAgentParser;
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct CaptchaVO {
@@ -25,6 +25,22 @@ pub struct TokenVO {
     pub refresh_token: String,
 }
 
+/// 登录结果：直接返回令牌，或返回挑战令牌，需继续完成后续步骤
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginVO {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// 登录挑战令牌（5 分钟有效）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge_token: Option<String>,
+    /// 下一步：verify 校验动态口令，enroll 绑定双因素认证，change_password 修改过期密码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge_type: Option<ChallengeKind>,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct LoginDTO {
     pub username: String,
@@ -32,41 +48,117 @@ pub struct LoginDTO {
     pub captcha: CaptchaDTO,
 }
 
-pub static CACHE: OnceLock<Cache<String, String>> = OnceLock::new();
-/// 验证码缓存
-#[derive(Debug, Clone, Copy)]
-pub struct CapCache;
+/// 登录时修改过期密码
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordDTO {
+    pub challenge_token: String,
+    pub new_password: String,
+}
+
+/// 用户注册
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterDTO {
+    pub username: String,
+    pub password: String,
+    pub nick_name: Option<String>,
+    pub phone_number: Option<String>,
+    pub email: Option<String>,
+    pub captcha: CaptchaDTO,
+}
+
+static CACHE_REQUESTS_TOTAL: Counter = Counter::new("cache_requests_total", "缓存查询次数");
+/// 验证码缓存（5 分钟有效），克隆后共享同一缓存
+#[derive(Clone)]
+pub struct CapCache(Cache<String, String>, MetricsTool);
 impl CapCache {
-    pub fn init_cache() -> &'static Cache<String, String> {
-        CACHE.get_or_init(|| {
+    pub fn new(metrics: MetricsTool) -> Self {
+        Self(
             Cache::builder()
                 .max_capacity(100)
                 .time_to_live(Duration::from_secs(300))
-                .build()
-        })
+                .build(),
+            metrics,
+        )
     }
-    pub fn get_cache() -> AppResult<&'static Cache<String, String>> {
-        let cache = CACHE
-            .get()
-            .ok_or(AppError::Other("验证码缓存获取失败".to_string()))?;
-        Ok(cache)
+
+    pub async fn insert(&self, k: &str, v: &str) {
+        self.0.insert(k.to_string(), v.to_string()).await;
     }
-    pub async fn insert(k: &str, v: &str) -> AppResult<()> {
-        Self::get_cache()?
-            .insert(k.to_string(), v.to_string())
-            .await;
-        Ok(())
+
+    pub async fn get(&self, k: &str) -> Option<String> {
+        let value = self.0.get(k).await;
+        let result = if value.is_some() { "hit" } else { "miss" };
+        CACHE_REQUESTS_TOTAL.inc(&self.1, &[("cache", "captcha"), ("result", result)]);
+        value
     }
 
-    pub async fn get(k: &str) -> AppResult<Option<String>> {
-        let cache = Self::get_cache()?;
-        Ok(cache.get(k).await)
+    pub async fn remove(&self, k: &str) -> Option<String> {
+        self.0.remove(k).await
     }
+}
+
+/// 挑战类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeKind {
+    /// 已启用双因素认证，需校验动态口令
+    Verify,
+    /// 角色策略要求启用但尚未绑定，需先完成绑定
+    Enroll,
+    /// 密码已过期，需先修改密码
+    ChangePassword,
+}
+
+/// 密码校验通过后的登录挑战，完成后续步骤后据此签发令牌
+#[derive(Debug, Clone)]
+pub struct LoginChallenge {
+    pub kind: ChallengeKind,
+    pub user_id: i32,
+    pub user_name: String,
+    pub ipaddr: String,
+    pub os: Option<String>,
+    pub browser: Option<String>,
+    /// 已失败次数
+    pub failures: u32,
+}
+
+/// 单个挑战允许的最大失败次数
+pub const MAX_CHALLENGE_FAILURES: u32 = 5;
 
-    pub async fn remove(k: &str) -> AppResult<Option<String>> {
-        let cache = Self::get_cache()?;
-        Ok(cache.remove(k).await)
+/// 登录挑战缓存（5 分钟有效），克隆后共享同一缓存
+#[derive(Debug, Clone)]
+pub struct ChallengeCache(Cache<String, LoginChallenge>);
+impl Default for ChallengeCache {
+    fn default() -> Self {
+        Self::new()
     }
 }
+impl ChallengeCache {
+    pub fn new() -> Self {
+        Self(
+            Cache::builder()
+                .max_capacity(1000)
+                .time_to_live(Duration::from_secs(300))
+                .build(),
+        )
+    }
+
+    pub async fn insert(&self, k: &str, v: LoginChallenge) {
+        self.0.insert(k.to_string(), v).await;
+    }
 
-pub static PASER: OnceLock<UserAgentParser> = OnceLock::new();
+    /// 获取指定类型的挑战，不存在、已过期或类型不符时视为令牌无效
+    pub async fn get(&self, k: &str, kind: ChallengeKind) -> AppResult<LoginChallenge> {
+        self.0
+            .get(k)
+            .await
+            .filter(|challenge| challenge.kind == kind)
+            .ok_or(AppError::TokenInvalid)
+    }
+
+    pub async fn remove(&self, k: &str) {
+        self.0.invalidate(k).await;
+    }
+}
@@ -0,0 +1,97 @@
+use common::page_reponse::PageReponse;
+use common::page_reqest::PageRequest;
+use common::{AppResult, response::ResponseResult};
+use framework::state::Db;
+use monitor::operlog::model::BusinessType;
+use salvo::oapi::endpoint;
+use salvo::oapi::extract::{JsonBody, PathParam};
+use salvo::{Depot, Writer};
+use tracing::info;
+
+use crate::handle::LogMeta;
+use crate::post::model::{ListPostQuery, PostDTO, SysPost};
+use crate::post::service;
+
+/// 岗位列表（分页）
+#[endpoint(tags("岗位管理"), summary = "岗位列表")]
+pub async fn page(
+    query_page: JsonBody<PageRequest<ListPostQuery>>,
+    db: Db,
+) -> AppResult<ResponseResult<PageReponse<SysPost>>> {
+    let query_page = query_page.into_inner();
+    info!("[HANDLER] Entering post::page with query: {:?}", query_page);
+    let page_data = service::page_post(&db, query_page).await?;
+    ResponseResult::success(page_data).into()
+}
+
+/// 全部可选岗位
+#[endpoint(tags("岗位管理"), summary = "岗位选择列表")]
+pub async fn options(db: Db) -> AppResult<ResponseResult<Vec<SysPost>>> {
+    info!("[HANDLER] Entering post::options");
+    let posts = service::select_post_all(&db).await?;
+    ResponseResult::success(posts).into()
+}
+
+/// 岗位详情
+#[endpoint(tags("岗位管理"), summary = "岗位详情")]
+pub async fn get_detail(post_id: PathParam<i32>, db: Db) -> AppResult<ResponseResult<SysPost>> {
+    let post_id = post_id.into_inner();
+    info!(
+        "[HANDLER] Entering post::get_detail with post_id: {}",
+        post_id
+    );
+    let post = service::select_post_by_id(&db, post_id).await?;
+    ResponseResult::success(post).into()
+}
+
+/// 新增岗位
+#[endpoint(tags("岗位管理"), summary = "新增岗位")]
+pub async fn add(
+    post: JsonBody<PostDTO>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let post = post.into_inner();
+    info!("[HANDLER] Entering post::add with body: {:?}", post);
+    LogMeta::set(depot, "岗位管理", BusinessType::Add.get_value(), "新增岗位");
+    service::add_post(&db, post).await?;
+    ResponseResult::success_msg("新增成功").into()
+}
+
+/// 修改岗位
+#[endpoint(tags("岗位管理"), summary = "修改岗位")]
+pub async fn update(
+    post: JsonBody<PostDTO>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let post = post.into_inner();
+    info!("[HANDLER] Entering post::update with body: {:?}", post);
+    LogMeta::set(
+        depot,
+        "岗位管理",
+        BusinessType::Update.get_value(),
+        "修改岗位",
+    );
+    service::update_post(&db, post).await?;
+    ResponseResult::success_msg("修改成功").into()
+}
+
+/// 删除岗位
+#[endpoint(tags("岗位管理"), summary = "删除岗位")]
+pub async fn delete(
+    post_id: PathParam<i32>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let post_id = post_id.into_inner();
+    info!("[HANDLER] Entering post::delete with post_id: {}", post_id);
+    LogMeta::set(
+        depot,
+        "岗位管理",
+        BusinessType::Delete.get_value(),
+        "删除岗位",
+    );
+    service::delete_post(&db, post_id).await?;
+    ResponseResult::success_msg("删除成功").into()
+}
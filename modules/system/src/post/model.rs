@@ -0,0 +1,44 @@
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+/// 岗位信息实体，与 `sys_post` 数据库表对应
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SysPost {
+    pub post_id: i32,
+    /// 岗位编码（唯一）
+    pub post_code: String,
+    pub post_name: String,
+    pub post_sort: i32,
+    /// 状态（0正常 1停用）
+    pub status: String,
+    pub create_by: Option<String>,
+    pub create_time: Option<OffsetDateTime>,
+    pub update_by: Option<String>,
+    pub update_time: Option<OffsetDateTime>,
+    pub remark: Option<String>,
+}
+
+/// 岗位列表查询参数
+#[derive(Deserialize, Debug, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPostQuery {
+    pub post_code: Option<String>,
+    pub post_name: Option<String>,
+    pub status: Option<String>,
+}
+
+/// 新增/修改岗位时接收前端数据的请求体
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PostDTO {
+    /// 修改时必须携带ID
+    pub post_id: Option<i32>,
+    pub post_code: String,
+    pub post_name: String,
+    pub post_sort: Option<i32>,
+    pub status: Option<String>,
+    pub remark: Option<String>,
+}
@@ -0,0 +1,41 @@
+use common::constants::Permission;
+use framework::midddleware::require_perm;
+use salvo::Router;
+
+use crate::post::handle::{add, delete, get_detail, options, page, update};
+
+pub fn init_router() -> Router {
+    Router::new()
+        .path("post")
+        .push(
+            Router::with_path("page")
+                .hoop(require_perm(Permission::PostList))
+                .post(page),
+        )
+        //用户编辑时选择岗位，只需用户查询权限
+        .push(
+            Router::with_path("options")
+                .hoop(require_perm(Permission::UserQuery))
+                .get(options),
+        )
+        .push(
+            Router::with_path("add")
+                .hoop(require_perm(Permission::PostAdd))
+                .post(add),
+        )
+        .push(
+            Router::with_path("update")
+                .hoop(require_perm(Permission::PostEdit))
+                .put(update),
+        )
+        .push(
+            Router::with_path("delete/{post_id}")
+                .hoop(require_perm(Permission::PostRemove))
+                .delete(delete),
+        )
+        .push(
+            Router::with_path("{post_id}")
+                .hoop(require_perm(Permission::PostQuery))
+                .get(get_detail),
+        )
+}
@@ -0,0 +1,187 @@
+use common::{
+    AppError, AppResult, SqlBuilder, page_reponse::PageReponse, page_reqest::PageRequest,
+};
+use sqlx::{PgPool, Postgres, Transaction};
+use tracing::info;
+
+use crate::post::model::{ListPostQuery, PostDTO, SysPost};
+
+/// 岗位编码是否已被其他岗位使用
+async fn post_code_exists(
+    db: &PgPool,
+    post_code: &str,
+    exclude_post_id: Option<i32>,
+) -> AppResult<bool> {
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM sys_post WHERE post_code = $1 AND post_id <> COALESCE($2, 0)
+        ) AS "exists!""#,
+        post_code,
+        exclude_post_id
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(exists)
+}
+
+/// 新增岗位
+pub(crate) async fn add_post(db: &PgPool, post: PostDTO) -> AppResult<i32> {
+    info!("[SERVICE] Entering add_post with dto: {:?}", post);
+    if post_code_exists(db, &post.post_code, None).await? {
+        return Err(AppError::ValidationFailed("岗位编码已存在".to_string()));
+    }
+    let post_id = sqlx::query_scalar!(
+        r#"
+            INSERT INTO sys_post (post_code, post_name, post_sort, status, remark, create_by, create_time)
+            VALUES ($1, $2, COALESCE($3, 0), COALESCE($4, '0'), $5, 'admin', NOW())
+            RETURNING post_id
+        "#,
+        post.post_code,
+        post.post_name,
+        post.post_sort,
+        post.status,
+        post.remark
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(post_id)
+}
+
+/// 修改岗位
+pub(crate) async fn update_post(db: &PgPool, post: PostDTO) -> AppResult<u64> {
+    info!("[SERVICE] Entering update_post with dto: {:?}", post);
+    let post_id = post
+        .post_id
+        .ok_or(AppError::ValidationFailed("岗位ID不能为空".to_string()))?;
+    if post_code_exists(db, &post.post_code, Some(post_id)).await? {
+        return Err(AppError::ValidationFailed("岗位编码已存在".to_string()));
+    }
+    let result = sqlx::query!(
+        r#"
+            UPDATE sys_post
+            SET post_code = $1, post_name = $2, post_sort = COALESCE($3, post_sort), status = COALESCE($4, status), remark = $5,
+                update_by = 'admin', update_time = NOW()
+            WHERE post_id = $6
+        "#,
+        post.post_code,
+        post.post_name,
+        post.post_sort,
+        post.status,
+        post.remark,
+        post_id
+    )
+    .execute(db)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::RecordNotFound);
+    }
+    Ok(result.rows_affected())
+}
+
+/// 删除岗位（仍分配给用户的岗位不允许删除）
+pub(crate) async fn delete_post(db: &PgPool, post_id: i32) -> AppResult<u64> {
+    info!("[SERVICE] Entering delete_post with post_id: {}", post_id);
+    let assigned = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM sys_user_post sup
+           INNER JOIN sys_user su ON su.user_id = sup.user_id
+           WHERE sup.post_id = $1 AND su.del_flag = '0'"#,
+        post_id
+    )
+    .fetch_one(db)
+    .await?;
+    if assigned > 0 {
+        return Err(AppError::ValidationFailed(format!(
+            "岗位已分配给 {} 个用户，不能删除",
+            assigned
+        )));
+    }
+    let mut tx = db.begin().await?;
+    // 清理已删除用户遗留的关联
+    sqlx::query!("DELETE FROM sys_user_post WHERE post_id = $1", post_id)
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query!("DELETE FROM sys_post WHERE post_id = $1", post_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// 根据岗位ID查询岗位详情
+pub(crate) async fn select_post_by_id(db: &PgPool, post_id: i32) -> AppResult<SysPost> {
+    info!(
+        "[SERVICE] Entering select_post_by_id with post_id: {}",
+        post_id
+    );
+    sqlx::query_as!(
+        SysPost,
+        "SELECT * FROM sys_post WHERE post_id = $1",
+        post_id
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or(AppError::RecordNotFound)
+}
+
+/// 查询全部正常状态的岗位（供用户编辑时选择）
+pub(crate) async fn select_post_all(db: &PgPool) -> AppResult<Vec<SysPost>> {
+    let posts = sqlx::query_as!(
+        SysPost,
+        "SELECT * FROM sys_post WHERE status = '0' ORDER BY post_sort"
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(posts)
+}
+
+/// 根据条件分页查询岗位列表
+pub(crate) async fn page_post(
+    db: &PgPool,
+    query_page: PageRequest<ListPostQuery>,
+) -> AppResult<PageReponse<SysPost>> {
+    info!("[SERVICE] Entering page_post with query: {:?}", query_page);
+    let mut sql_builder = SqlBuilder::for_pagination(db, "*", "sys_post", None);
+    sql_builder
+        .where_like("post_code", query_page.query.post_code.as_deref())
+        .where_like("post_name", query_page.query.post_name.as_deref())
+        .where_eq("status", query_page.query.status)
+        .order_by("post_sort", None);
+    sql_builder
+        .fetch_paged(query_page.page, query_page.page_size)
+        .await
+}
+
+/// 根据用户ID查询岗位ID列表
+pub async fn select_post_ids_by_user_id(db: &PgPool, user_id: i32) -> AppResult<Vec<i32>> {
+    let post_ids = sqlx::query_scalar!(
+        "SELECT post_id FROM sys_user_post WHERE user_id = $1",
+        user_id
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(post_ids)
+}
+
+/// 在事务中重新设置用户的岗位
+pub async fn set_user_posts(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: i32,
+    post_ids: &[i32],
+) -> Result<(), AppError> {
+    info!(
+        "[TX_HELPER] Setting {} post associations for user_id: {}",
+        post_ids.len(),
+        user_id
+    );
+    sqlx::query!("DELETE FROM sys_user_post WHERE user_id = $1", user_id)
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query!(
+        "INSERT INTO sys_user_post (user_id, post_id) SELECT $1, * FROM UNNEST($2::int[])",
+        user_id,
+        post_ids
+    )
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
@@ -3,7 +3,10 @@ use crate::role::service;
 use common::page_reponse::PageReponse;
 use common::page_reqest::PageRequest;
 use common::{AppResult, response::ResponseResult};
-use framework::db::DBPool;
+use framework::{
+    permission::PermTool,
+    state::{Db, Ext},
+};
 use salvo::Writer;
 use salvo::oapi::endpoint;
 use salvo::oapi::extract::JsonBody;
@@ -13,52 +16,61 @@ use tracing::info;
 
 /// 新增角色
 #[endpoint(tags("角色管理"))]
-pub async fn add(role: JsonBody<RoleDTO>) -> AppResult<ResponseResult<()>> {
+pub async fn add(role: JsonBody<RoleDTO>, db: Db) -> AppResult<ResponseResult<()>> {
     let role = role.into_inner();
     info!("[HANDLER] Entering role::insert  with body: {:?}", role);
-    let db = DBPool::get().await?;
-    service::add_role(db, role).await?;
+    service::add_role(&db, role).await?;
     ResponseResult::success_msg("新增成功").into()
 }
 
 /// 删除角色
 #[endpoint(tags("角色管理"))]
-pub async fn delete(role_id: PathParam<i32>) -> AppResult<ResponseResult<()>> {
+pub async fn delete(
+    role_id: PathParam<i32>,
+    db: Db,
+    perm_tool: Ext<PermTool>,
+) -> AppResult<ResponseResult<()>> {
     info!(
         "[HANDLER] Entering role::delete  with role_id: {:?}",
         role_id
     );
-    let db = DBPool::get().await?;
-    service::delete_role(db, role_id.into_inner()).await?;
+    service::delete_role(&db, role_id.into_inner()).await?;
+    perm_tool.invalidate_all();
     ResponseResult::success_msg("删除成功").into()
 }
 
 /// 修改角色
 #[endpoint(tags("角色管理"))]
-pub async fn update(role: JsonBody<RoleDTO>) -> AppResult<ResponseResult<()>> {
+pub async fn update(
+    role: JsonBody<RoleDTO>,
+    db: Db,
+    perm_tool: Ext<PermTool>,
+) -> AppResult<ResponseResult<()>> {
     let role = role.into_inner();
     info!("[HANDLER] Entering role::update  with body: {:?}", role);
     if role.role_id.is_none() {
         return Err(common::AppError::Other("角色ID不能为空".to_string()));
     }
-    let db = DBPool::get().await?;
-    service::update_role(db, role).await?;
+    service::update_role(&db, role).await?;
+    perm_tool.invalidate_all();
     ResponseResult::success_msg("修改成功").into()
 }
 
 /// 获取角色详情
 #[endpoint(tags("角色管理"))]
-pub async fn get_detail(role_id: PathParam<i32>) -> AppResult<ResponseResult<Value>> {
+pub async fn get_detail(role_id: PathParam<i32>, db: Db) -> AppResult<ResponseResult<Value>> {
     let role_id = role_id.into_inner();
     info!("[HANDLER] Entering role::get  with role_id: {:?}", role_id);
-    let db = DBPool::get().await?;
     //1. 先查询角色基本信息
-    let role = service::select_by_id(db, role_id).await?;
+    let role = service::select_by_id(&db, role_id).await?;
     //2. 再查询角色对应的菜单列表
-    let menu_ids = service::select_menu_ids_by_role_id(db, role_id).await?;
+    let menu_ids = service::select_menu_ids_by_role_id(&db, role_id).await?;
+    //3. 自定义数据权限关联的部门
+    let dept_ids = service::select_dept_ids_by_role_id(&db, role_id).await?;
     let data = json!({
          "role": role,
-         "menu_ids": menu_ids
+         "menu_ids": menu_ids,
+         "dept_ids": dept_ids
     });
     ResponseResult::success(data).into()
 }
@@ -67,6 +79,8 @@ pub async fn get_detail(role_id: PathParam<i32>) -> AppResult<ResponseResult<Val
 pub async fn change_status(
     role_id: QueryParam<u32>,
     status: QueryParam<String>,
+    db: Db,
+    perm_tool: Ext<PermTool>,
 ) -> AppResult<ResponseResult<()>> {
     let role_id = role_id.into_inner();
     let status = status.into_inner();
@@ -74,8 +88,8 @@ pub async fn change_status(
         "[HANDLER] Entering role::change_status  with role_id: {:?}, status: {:?}",
         role_id, status
     );
-    let db = DBPool::get().await?;
-    service::change_status(db, role_id, status).await?;
+    service::change_status(&db, role_id, status).await?;
+    perm_tool.invalidate_all();
     ResponseResult::success_msg("状态修改成功").into()
 }
 
@@ -83,13 +97,13 @@ pub async fn change_status(
 #[endpoint(tags("角色管理"))]
 pub async fn page(
     query_page: JsonBody<PageRequest<ListRoleQuery>>,
+    db: Db,
 ) -> AppResult<ResponseResult<PageReponse<SysRole>>> {
     let query_page = query_page.into_inner();
     info!(
         "[HANDLER] Entering role::page  with query: {:?}",
         query_page
     );
-    let db = DBPool::get().await?;
-    let page_data = service::page_role(db, query_page).await?;
+    let page_data = service::page_role(&db, query_page).await?;
     ResponseResult::success(page_data).into()
 }
@@ -19,6 +19,8 @@ pub struct SysRole {
     pub update_by: Option<String>,
     pub update_time: Option<OffsetDateTime>,
     pub remark: Option<String>,
+    /// 强制双因素认证（0否 1是）
+    pub force_mfa: Option<String>,
 }
 
 /// 用于角色列表查询的参数结构体
@@ -46,8 +48,14 @@ pub struct RoleDTO {
     pub role_sort: i32,
     pub status: String,
     pub remark: Option<String>,
+    /// 强制双因素认证（0否 1是）
+    pub force_mfa: Option<String>,
+    /// 数据范围（1全部 2自定义 3本部门 4本部门及以下），为空时新增默认全部、修改保持不变
+    pub data_scope: Option<String>,
     // 修改角色时，也可能重新关联菜单
     pub menu_ids: Option<Vec<i32>>,
+    /// 自定义数据权限关联的部门，为空时不修改
+    pub dept_ids: Option<Vec<i32>>,
 }
 
 /// 修改角色状态时使用的请求体
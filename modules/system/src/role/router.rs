@@ -1,13 +1,39 @@
+use common::constants::Permission;
+use framework::midddleware::require_perm;
 use salvo::Router;
 
 use crate::role::handle::*;
 pub fn init_router() -> Router {
     Router::new()
         .path("role")
-        .push(Router::with_path("add").post(add))
-        .push(Router::with_path("delete/{role_id}").delete(delete))
-        .push(Router::with_path("update").put(update))
-        .push(Router::with_path("{role_id}").get(get_detail))
-        .push(Router::with_path("page").get(page))
-        .push(Router::with_path("change_status").get(change_status))
+        .push(
+            Router::with_path("add")
+                .hoop(require_perm(Permission::RoleAdd))
+                .post(add),
+        )
+        .push(
+            Router::with_path("delete/{role_id}")
+                .hoop(require_perm(Permission::RoleRemove))
+                .delete(delete),
+        )
+        .push(
+            Router::with_path("update")
+                .hoop(require_perm(Permission::RoleEdit))
+                .put(update),
+        )
+        .push(
+            Router::with_path("{role_id}")
+                .hoop(require_perm(Permission::RoleQuery))
+                .get(get_detail),
+        )
+        .push(
+            Router::with_path("page")
+                .hoop(require_perm(Permission::RoleList))
+                .get(page),
+        )
+        .push(
+            Router::with_path("change_status")
+                .hoop(require_perm(Permission::RoleEdit))
+                .get(change_status),
+        )
 }
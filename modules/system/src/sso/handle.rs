@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use common::{AppError, AppResult, response::ResponseResult};
+use framework::{
+    oidc::{OidcTool, SSO_STATE_COOKIE, clear_state_cookie},
+    state::{Db, Ext, State},
+};
+use salvo::oapi::endpoint;
+use salvo::oapi::extract::{PathParam, QueryParam};
+use salvo::{Depot, Request, Response, Writer};
+use tracing::{info, warn};
+use user_agent_parser::UserAgentParser;
+
+use crate::handle::{client_info, continue_login, current_user_id, record_login_log};
+use crate::model::LoginVO;
+use crate::sso::{model::SsoAuthorizeVO, service};
+
+/// 获取单点登录授权地址
+#[endpoint(tags("单点登录"), summary = "获取授权地址")]
+pub async fn authorize(
+    provider: PathParam<String>,
+    res: &mut Response,
+    oidc: Ext<OidcTool>,
+) -> AppResult<ResponseResult<SsoAuthorizeVO>> {
+    let provider = provider.into_inner();
+    info!(
+        "[HANDLER] Entering sso::authorize with provider: {}",
+        provider
+    );
+    let request = oidc.authorize_url(&provider, None).await?;
+    res.add_cookie(request.state_cookie);
+    ResponseResult::success(SsoAuthorizeVO {
+        authorize_url: request.url,
+    })
+    .into()
+}
+
+/// 单点登录回调：校验 ID Token，登录已绑定（或自动创建）的用户，或完成账号绑定
+#[allow(clippy::too_many_arguments)]
+#[endpoint(tags("单点登录"), summary = "授权回调")]
+pub async fn callback(
+    provider: PathParam<String>,
+    code: QueryParam<String, false>,
+    state: QueryParam<String, false>,
+    error: QueryParam<String, false>,
+    req: &mut Request,
+    res: &mut Response,
+    app_state: State,
+    oidc: Ext<OidcTool>,
+    ua_parser: Ext<Arc<UserAgentParser>>,
+) -> AppResult<ResponseResult<LoginVO>> {
+    let provider = provider.into_inner();
+    info!(
+        "[HANDLER] Entering sso::callback with provider: {}",
+        provider
+    );
+    if let Some(error) = error.into_inner() {
+        return Err(AppError::SsoAuthFailed(format!("提供方拒绝授权: {error}")));
+    }
+    let (Some(code), Some(state)) = (code.into_inner(), state.into_inner()) else {
+        return Err(AppError::SsoAuthFailed("缺少授权码".to_string()));
+    };
+    let db = app_state.db();
+    let state_cookie = req.cookie(SSO_STATE_COOKIE).map(|c| c.value().to_string());
+    res.add_cookie(clear_state_cookie());
+    let (pending, claims) = oidc
+        .exchange(&provider, &state, &code, state_cookie.as_deref())
+        .await?;
+
+    // 已登录用户发起的绑定
+    if let Some(user_id) = pending.link_user_id {
+        service::link(db, &provider, &claims, user_id).await?;
+        return Ok(ResponseResult::success_with_msg(
+            "绑定成功",
+            LoginVO {
+                access_token: None,
+                refresh_token: None,
+                challenge_token: None,
+                challenge_type: None,
+            },
+        ));
+    }
+
+    let (ipaddr, os, browser) = client_info(req, &ua_parser);
+    let user = match service::select_user_by_subject(db, &provider, &claims.sub).await? {
+        Some(user) => user,
+        None if oidc.provider(&provider)?.auto_provision => {
+            service::provision(db, oidc.provider(&provider)?, &claims).await?
+        }
+        None => {
+            warn!(
+                "[HANDLER] '{}' account '{}' is not linked to any user",
+                provider, claims.sub
+            );
+            return Err(AppError::SsoAuthFailed(
+                "该外部账号未绑定系统用户".to_string(),
+            ));
+        }
+    };
+    if user.status.as_deref() != Some("0") {
+        record_login_log(
+            &app_state,
+            user.user_name.clone(),
+            ipaddr,
+            os,
+            browser,
+            "1",
+            "账号已停用或待审核".to_string(),
+        )
+        .await;
+        return Err(AppError::ValidationFailed(
+            "账号已停用或正在等待审核".to_string(),
+        ));
+    }
+    // 密码有效期不适用于单点登录，双因素认证策略仍然生效
+    continue_login(&app_state, &user, ipaddr, os, browser, false).await
+}
+
+/// 已登录用户绑定外部账号：获取授权地址，回调后完成绑定
+#[endpoint(tags("单点登录"), summary = "绑定外部账号")]
+pub async fn link(
+    provider: PathParam<String>,
+    depot: &mut Depot,
+    res: &mut Response,
+    oidc: Ext<OidcTool>,
+) -> AppResult<ResponseResult<SsoAuthorizeVO>> {
+    let provider = provider.into_inner();
+    let user_id = current_user_id(depot)?;
+    info!(
+        "[HANDLER] Entering sso::link with provider: {}, user_id: {}",
+        provider, user_id
+    );
+    let request = oidc.authorize_url(&provider, Some(user_id)).await?;
+    res.add_cookie(request.state_cookie);
+    ResponseResult::success(SsoAuthorizeVO {
+        authorize_url: request.url,
+    })
+    .into()
+}
+
+/// 已登录用户解绑外部账号
+#[endpoint(tags("单点登录"), summary = "解绑外部账号")]
+pub async fn unlink(
+    provider: PathParam<String>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let provider = provider.into_inner();
+    let user_id = current_user_id(depot)?;
+    info!(
+        "[HANDLER] Entering sso::unlink with provider: {}, user_id: {}",
+        provider, user_id
+    );
+    if service::unlink(&db, &provider, user_id).await? == 0 {
+        return Err(AppError::ValidationFailed("未绑定该登录方式".to_string()));
+    }
+    ResponseResult::success_msg("解绑成功").into()
+}
@@ -0,0 +1,6 @@
+pub mod handle;
+pub mod model;
+pub mod router;
+pub mod service;
+
+pub use router::{init_login_router, init_router};
@@ -0,0 +1,11 @@
+use salvo::oapi::ToSchema;
+use serde::Serialize;
+
+/// 单点登录授权地址，前端跳转到该地址完成认证
+///
+/// 响应同时写入 HttpOnly 的 state Cookie，回调请求须携带该 Cookie
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SsoAuthorizeVO {
+    pub authorize_url: String,
+}
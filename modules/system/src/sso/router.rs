@@ -0,0 +1,19 @@
+use salvo::Router;
+
+use crate::sso::handle;
+
+/// 单点登录（无需认证）
+pub fn init_login_router() -> Router {
+    Router::new()
+        .path("sso/{provider}")
+        .push(Router::with_path("authorize").get(handle::authorize))
+        .push(Router::with_path("callback").get(handle::callback))
+}
+
+/// 已登录用户绑定、解绑外部账号
+pub fn init_router() -> Router {
+    Router::new()
+        .path("sso/{provider}")
+        .push(Router::with_path("link").post(handle::link))
+        .push(Router::with_path("unlink").post(handle::unlink))
+}
@@ -0,0 +1,202 @@
+use common::{AppError, AppResult};
+use framework::{config::OidcProvider, oidc::IdTokenClaims};
+use sqlx::PgPool;
+use tracing::info;
+
+use crate::user::{
+    self,
+    model::SysUser,
+    service::{insert_user_role_by_keys, select_user_entity},
+};
+
+/// 根据外部账号查询已绑定的用户
+pub(crate) async fn select_user_by_subject(
+    db: &PgPool,
+    provider: &str,
+    subject: &str,
+) -> AppResult<Option<SysUser>> {
+    let user = sqlx::query_as!(
+        SysUser,
+        "SELECT su.* FROM sys_user su
+         INNER JOIN sys_user_sso ss ON su.user_id = ss.user_id
+         WHERE ss.provider = $1 AND ss.subject = $2 AND su.del_flag = '0'",
+        provider,
+        subject
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(user)
+}
+
+/// 将外部账号绑定到用户，同一提供方下一个用户只能绑定一个外部账号
+pub(crate) async fn link(
+    db: &PgPool,
+    provider: &str,
+    claims: &IdTokenClaims,
+    user_id: i32,
+) -> AppResult<()> {
+    info!(
+        "[SERVICE] Linking '{}' account '{}' to user_id: {}",
+        provider, claims.sub, user_id
+    );
+    let linked = sqlx::query!(
+        "SELECT user_id FROM sys_user_sso WHERE provider = $1 AND (subject = $2 OR user_id = $3)",
+        provider,
+        claims.sub,
+        user_id
+    )
+    .fetch_optional(db)
+    .await?;
+    match linked {
+        Some(row) if row.user_id == user_id => Err(AppError::ValidationFailed(
+            "当前用户已绑定该登录方式".to_string(),
+        )),
+        Some(_) => Err(AppError::ValidationFailed(
+            "该外部账号已绑定其他用户".to_string(),
+        )),
+        None => {
+            sqlx::query!(
+                "INSERT INTO sys_user_sso (provider, subject, user_id, email) VALUES ($1, $2, $3, $4)",
+                provider,
+                claims.sub,
+                user_id,
+                claims.email
+            )
+            .execute(db)
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+/// 解除绑定
+pub(crate) async fn unlink(db: &PgPool, provider: &str, user_id: i32) -> AppResult<u64> {
+    info!(
+        "[SERVICE] Unlinking '{}' account of user_id: {}",
+        provider, user_id
+    );
+    let result = sqlx::query!(
+        "DELETE FROM sys_user_sso WHERE provider = $1 AND user_id = $2",
+        provider,
+        user_id
+    )
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// 首次登录自动创建用户并绑定外部账号（无本地密码，只能通过单点登录登录）
+pub(crate) async fn provision(
+    db: &PgPool,
+    provider: &OidcProvider,
+    claims: &IdTokenClaims,
+) -> AppResult<SysUser> {
+    let user_name = available_user_name(db, provider, claims).await?;
+    info!(
+        "[SERVICE] Provisioning user '{}' for '{}' account '{}'",
+        user_name, provider.name, claims.sub
+    );
+    let nick_name = claims.name.as_deref().unwrap_or(&user_name);
+    let email = verified_email(claims);
+
+    let mut tx = db.begin().await?;
+    let user_id = sqlx::query_scalar!(
+        "INSERT INTO sys_user (user_name, nick_name, user_type, email, status, create_by, create_time, remark)
+         VALUES ($1, $2, '01', $3, '0', $4, NOW(), '单点登录自动创建') RETURNING user_id",
+        user_name,
+        nick_name,
+        email,
+        provider.name
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    insert_user_role_by_keys(&mut tx, user_id, &provider.default_roles).await?;
+    sqlx::query!(
+        "INSERT INTO sys_user_sso (provider, subject, user_id, email) VALUES ($1, $2, $3, $4)",
+        provider.name,
+        claims.sub,
+        user_id,
+        claims.email
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    select_user_entity(db, user_id).await
+}
+
+/// 仅使用已验证的邮箱
+fn verified_email(claims: &IdTokenClaims) -> Option<&str> {
+    claims
+        .email
+        .as_deref()
+        .filter(|_| claims.email_verified == Some(true))
+}
+
+/// 候选用户名：外部账号的用户名、已验证的邮箱，最后是 `{提供方}_{sub}`
+fn user_name_candidates(provider: &str, claims: &IdTokenClaims) -> Vec<String> {
+    const MAX_LEN: usize = 30;
+    let fallback: String = format!("{}_{}", provider, claims.sub)
+        .chars()
+        .take(MAX_LEN)
+        .collect();
+    [
+        claims.preferred_username.as_deref(),
+        verified_email(claims),
+        Some(fallback.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|candidate| !candidate.is_empty() && candidate.chars().count() <= MAX_LEN)
+    .map(str::to_string)
+    .collect()
+}
+
+/// 优先使用外部账号的用户名或已验证的邮箱，已被占用时使用 `{提供方}_{sub}`
+async fn available_user_name(
+    db: &PgPool,
+    provider: &OidcProvider,
+    claims: &IdTokenClaims,
+) -> AppResult<String> {
+    for candidate in user_name_candidates(&provider.name, claims) {
+        if user::service::select_user_by_username(db, &candidate)
+            .await?
+            .is_none()
+        {
+            return Ok(candidate);
+        }
+    }
+    Err(AppError::SsoAuthFailed(
+        "无法为外部账号生成可用的用户名".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use framework::oidc::IdTokenClaims;
+    use serde_json::json;
+
+    use super::user_name_candidates;
+
+    fn claims(email_verified: Option<bool>) -> IdTokenClaims {
+        serde_json::from_value(json!({
+            "sub": "42",
+            "email": "alice@example.com",
+            "email_verified": email_verified,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn user_name_candidates_test() {
+        assert_eq!(
+            user_name_candidates("github", &claims(Some(true))),
+            ["alice@example.com", "github_42"]
+        );
+        // 未验证的邮箱不能作为用户名
+        assert_eq!(
+            user_name_candidates("github", &claims(Some(false))),
+            ["github_42"]
+        );
+        assert_eq!(user_name_candidates("github", &claims(None)), ["github_42"]);
+    }
+}
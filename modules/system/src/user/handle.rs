@@ -2,18 +2,28 @@ use common::AppError;
 use common::page_reponse::PageReponse;
 use common::page_reqest::PageRequest;
 use common::{AppResult, response::ResponseResult};
-use framework::db::DBPool;
+use framework::{
+    password::PasswordPolicy,
+    permission::PermTool,
+    state::{Config, Db, Ext},
+};
 use monitor::operlog::model::BusinessType;
 use salvo::oapi::endpoint;
 use salvo::oapi::extract::PathParam;
 use salvo::oapi::extract::{JsonBody, QueryParam};
-use salvo::{Depot, Writer};
+use salvo::{Depot, Request, Writer};
+use sqlx::PgPool;
 use tracing::info;
 
-use crate::handle::LogMeta;
+use crate::dept;
+use crate::file;
+use crate::handle::{LogMeta, current_user_id};
+use crate::post;
 use crate::role;
 use crate::user::model::SysUserVO;
-use crate::user::model::{SysUserAddDTO, SysUserUpdateDTO};
+use crate::user::model::{
+    ProfilePasswordDTO, ProfileUpdateDTO, ProfileVO, ResetPwdDTO, SysUserAddDTO, SysUserUpdateDTO,
+};
 use crate::user::{self, model};
 
 /// 添加用户
@@ -21,40 +31,49 @@ use crate::user::{self, model};
 pub async fn add_user(
     user: JsonBody<SysUserAddDTO>,
     depot: &mut Depot,
+    db: Db,
+    policy: Ext<PasswordPolicy>,
 ) -> AppResult<ResponseResult<()>> {
     info!("[HANDLER_ADD] Entering clean 'add' handler.");
 
     //添加日志
     LogMeta::set(depot, "用户管理", BusinessType::Add.get_value(), "添加用户");
     let user = user.into_inner();
-    let db = DBPool::get().await?;
-    //1.控制user_name唯一性
-    if user::service::select_user_by_username(db, &user.phone_number)
+    //1.所属部门须在数据权限范围内
+    check_dept_scope(&db, depot, user.dept_id).await?;
+    //2.控制user_name唯一性
+    if user::service::select_user_by_username(&db, &user.phone_number)
         .await?
         .is_some()
     {
         return Err(AppError::Other("手机号已存在".to_string()));
     }
 
-    //2.添加用户
-    user::service::add_user(db, user).await?;
+    //3.添加用户
+    user::service::add_user(&db, &policy, user).await?;
     ResponseResult::success_msg("添加成功").into()
 }
 
 /// 查看用户信息
 #[endpoint(tags("用户管理"), summary = "查看用户信息")]
-pub async fn get_detail(user_id: PathParam<i32>) -> AppResult<ResponseResult<SysUserVO>> {
+pub async fn get_detail(
+    user_id: PathParam<i32>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<SysUserVO>> {
     let user_id = user_id.into_inner();
     info!(
         "[HANDLER] Entering user::get_detail with user_id: {}",
         user_id
     );
-    let db = DBPool::get().await?;
+    check_data_scope(&db, depot, user_id).await?;
     //1. 查询用户信息
-    let mut user_vo = user::service::select_user_by_id(db, user_id).await?;
+    let mut user_vo = user::service::select_user_by_id(&db, user_id).await?;
     //2. 查询关联的角色信息
-    let role_list = role::service::select_role_list_by_user_id(db, user_id).await?;
+    let role_list = role::service::select_role_list_by_user_id(&db, user_id).await?;
     user_vo.role_list = Some(role_list);
+    //3. 查询关联的岗位
+    user_vo.post_ids = Some(post::service::select_post_ids_by_user_id(&db, user_id).await?);
     ResponseResult::success(user_vo).into()
 }
 
@@ -62,12 +81,15 @@ pub async fn get_detail(user_id: PathParam<i32>) -> AppResult<ResponseResult<Sys
 #[endpoint(tags("用户管理"))]
 pub async fn page(
     page_query: JsonBody<PageRequest<model::ListUserQuery>>,
+    depot: &mut Depot,
+    db: Db,
 ) -> AppResult<ResponseResult<PageReponse<SysUserVO>>> {
     info!("[HANDLER] Entering user::page_list.");
-    let db = DBPool::get().await?;
-    let mut user_list = user::service::select_user_page(db, page_query.into_inner()).await?;
+    let data_scope = dept::service::select_data_scope(&db, current_user_id(depot)?).await?;
+    let mut user_list =
+        user::service::select_user_page(&db, page_query.into_inner(), &data_scope).await?;
     for user in user_list.items.iter_mut() {
-        let role_list = role::service::select_role_list_by_user_id(db, user.user_id).await?;
+        let role_list = role::service::select_role_list_by_user_id(&db, user.user_id).await?;
         user.role_list = Some(role_list);
     }
     ResponseResult::success(user_list).into()
@@ -76,16 +98,15 @@ pub async fn page(
 /// 修改用户密码
 #[endpoint(tags("用户管理"))]
 pub async fn reset_pwd(
-    user_id: QueryParam<i32>,
-    pwd: QueryParam<String>,
+    dto: JsonBody<ResetPwdDTO>,
     depot: &mut Depot,
+    db: Db,
+    policy: Ext<PasswordPolicy>,
 ) -> AppResult<ResponseResult<()>> {
-    let user_id: i32 = user_id.into_inner();
-    let pwd = pwd.into_inner();
-
+    let ResetPwdDTO { user_id, password } = dto.into_inner();
     info!(
-        "[HANDLER] Entering user::update_pwd with user_id: {}, pwd: {}",
-        user_id, pwd
+        "[HANDLER] Entering user::reset_pwd with user_id: {}",
+        user_id
     );
 
     //添加日志
@@ -95,14 +116,39 @@ pub async fn reset_pwd(
         BusinessType::Update.get_value(),
         "修改密码",
     );
-    let db = DBPool::get().await?;
-    user::service::reset_user_password(db, user_id, &pwd).await?;
+    check_data_scope(&db, depot, user_id).await?;
+    user::service::reset_user_password(&db, &policy, user_id, &password).await?;
     ResponseResult::success_msg("修改密码成功").into()
 }
 
+/// 审核通过自助注册的用户（启用账号）
+#[endpoint(tags("用户管理"))]
+pub async fn approve(
+    user_id: PathParam<i32>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let user_id = user_id.into_inner();
+    info!("[HANDLER] Entering user::approve with user_id: {}", user_id);
+    LogMeta::set(
+        depot,
+        "用户管理",
+        BusinessType::Update.get_value(),
+        "审核注册用户",
+    );
+    check_data_scope(&db, depot, user_id).await?;
+    user::service::change_user_status(&db, user_id, "0").await?;
+    ResponseResult::success_msg("审核通过").into()
+}
+
 /// 删除用户
 #[endpoint(tags("用户管理"))]
-pub async fn delete(user_id: PathParam<i32>, depot: &mut Depot) -> AppResult<ResponseResult<()>> {
+pub async fn delete(
+    user_id: PathParam<i32>,
+    depot: &mut Depot,
+    db: Db,
+    perm_tool: Ext<PermTool>,
+) -> AppResult<ResponseResult<()>> {
     let user_id = user_id.into_inner();
     info!("[HANDLER] Entering user::delete with user_id: {}", user_id);
     //添加日志
@@ -112,8 +158,9 @@ pub async fn delete(user_id: PathParam<i32>, depot: &mut Depot) -> AppResult<Res
         BusinessType::Delete.get_value(),
         "删除用户",
     );
-    let db = DBPool::get().await?;
-    user::service::delete(db, user_id).await?;
+    check_data_scope(&db, depot, user_id).await?;
+    user::service::delete(&db, user_id).await?;
+    perm_tool.invalidate(user_id).await;
     ResponseResult::success_msg("删除成功").into()
 }
 
@@ -122,6 +169,8 @@ pub async fn delete(user_id: PathParam<i32>, depot: &mut Depot) -> AppResult<Res
 pub async fn update_user(
     user: JsonBody<SysUserUpdateDTO>,
     depot: &mut Depot,
+    db: Db,
+    perm_tool: Ext<PermTool>,
 ) -> AppResult<ResponseResult<()>> {
     info!(
         "[HANDLER_UPDATE] Entering clean 'update' handler. user: {:?}",
@@ -135,18 +184,41 @@ pub async fn update_user(
         "修改用户",
     );
     let user = user.into_inner();
-    let db = DBPool::get().await?;
 
+    //1.校验数据权限
+    let user_id = user.user_id;
+    check_data_scope(&db, depot, user_id).await?;
+    check_dept_scope(&db, depot, user.dept_id).await?;
     //2.修改用户
-    user::service::update_user(db, user).await?;
+    user::service::update_user(&db, user).await?;
+    //3.角色可能变更，清除权限缓存
+    perm_tool.invalidate(user_id).await;
     ResponseResult::success_msg("修改成功").into()
 }
 
+/// 校验目标用户是否在当前用户的数据权限范围内
+async fn check_data_scope(db: &PgPool, depot: &Depot, user_id: i32) -> AppResult<()> {
+    let data_scope = dept::service::select_data_scope(db, current_user_id(depot)?).await?;
+    user::service::check_user_data_scope(db, &data_scope, user_id).await
+}
+
+/// 校验部门是否在当前用户的数据权限范围内，防止把用户添加或移动到范围外的部门
+async fn check_dept_scope(db: &PgPool, depot: &Depot, dept_id: Option<i32>) -> AppResult<()> {
+    let data_scope = dept::service::select_data_scope(db, current_user_id(depot)?).await?;
+    if !data_scope.contains_dept(dept_id) {
+        return Err(AppError::PermissionDenied);
+    }
+    Ok(())
+}
+
 /// 修改用户角色
 #[endpoint(tags("用户管理"))]
 pub async fn update_user_roles(
     user_id: QueryParam<i32>,
     role_ids: QueryParam<Vec<i32>>,
+    depot: &mut Depot,
+    db: Db,
+    perm_tool: Ext<PermTool>,
 ) -> AppResult<ResponseResult<()>> {
     let user_id = user_id.into_inner();
     let role_ids = role_ids.into_inner();
@@ -154,9 +226,106 @@ pub async fn update_user_roles(
         "[HANDLER_UPDATE] Entering clean 'update_user_roles' handler. user_id: {}, role_ids: {:?}",
         user_id, role_ids
     );
-    let db = DBPool::get().await?;
+    check_data_scope(&db, depot, user_id).await?;
     if !role_ids.is_empty() {
-        user::service::update_user_roles(db, user_id, &role_ids).await?;
+        user::service::update_user_roles(&db, user_id, &role_ids).await?;
+        perm_tool.invalidate(user_id).await;
     }
     ResponseResult::success_msg("修改成功").into()
 }
+
+/// 个人中心：查看本人信息及角色、权限
+#[endpoint(tags("个人中心"), summary = "查看个人信息")]
+pub async fn profile(depot: &mut Depot, db: Db) -> AppResult<ResponseResult<ProfileVO>> {
+    let user_id = current_user_id(depot)?;
+    info!("[HANDLER] Entering user::profile with user_id: {}", user_id);
+    let mut user = user::service::select_user_by_id(&db, user_id).await?;
+    user.role_list = Some(role::service::select_role_list_by_user_id(&db, user_id).await?);
+    let roles = user::service::get_user_roles(&db, user_id).await?;
+    let permissions = user::service::get_user_permissions(&db, user_id).await?;
+    ResponseResult::success(ProfileVO {
+        user,
+        roles,
+        permissions,
+    })
+    .into()
+}
+
+/// 个人中心：修改昵称、邮箱、手机号
+#[endpoint(tags("个人中心"), summary = "修改个人资料")]
+pub async fn update_profile(
+    dto: JsonBody<ProfileUpdateDTO>,
+    depot: &mut Depot,
+    db: Db,
+) -> AppResult<ResponseResult<()>> {
+    let user_id = current_user_id(depot)?;
+    info!(
+        "[HANDLER] Entering user::update_profile with user_id: {}",
+        user_id
+    );
+    LogMeta::set(
+        depot,
+        "个人中心",
+        BusinessType::Update.get_value(),
+        "修改个人资料",
+    );
+    user::service::update_profile(&db, user_id, dto.into_inner()).await?;
+    ResponseResult::success_msg("修改成功").into()
+}
+
+/// 个人中心：上传头像（表单字段 `avatar`），返回头像地址
+#[endpoint(tags("个人中心"), summary = "上传头像")]
+pub async fn update_avatar(
+    req: &mut Request,
+    depot: &mut Depot,
+    db: Db,
+    config: Config,
+) -> AppResult<ResponseResult<String>> {
+    let user_id = current_user_id(depot)?;
+    info!(
+        "[HANDLER] Entering user::update_avatar with user_id: {}",
+        user_id
+    );
+    let file = req
+        .file("avatar")
+        .await
+        .ok_or(AppError::ValidationFailed("请选择头像文件".to_string()))?;
+    let is_image = file
+        .content_type()
+        .is_some_and(|mime| mime.type_() == "image");
+    if !is_image {
+        return Err(AppError::ValidationFailed("头像必须是图片".to_string()));
+    }
+    let avatar = file::handle::save(file, &config.upload)?
+        .to_string_lossy()
+        .to_string();
+    user::service::update_avatar(&db, user_id, &avatar).await?;
+    ResponseResult::success_with_msg("头像修改成功", avatar).into()
+}
+
+/// 个人中心：校验旧密码后修改密码
+#[endpoint(tags("个人中心"), summary = "修改密码")]
+pub async fn change_password(
+    dto: JsonBody<ProfilePasswordDTO>,
+    depot: &mut Depot,
+    db: Db,
+    policy: Ext<PasswordPolicy>,
+) -> AppResult<ResponseResult<()>> {
+    let user_id = current_user_id(depot)?;
+    info!(
+        "[HANDLER] Entering user::change_password with user_id: {}",
+        user_id
+    );
+    LogMeta::set(
+        depot,
+        "个人中心",
+        BusinessType::Update.get_value(),
+        "修改密码",
+    );
+    let ProfilePasswordDTO {
+        old_password,
+        new_password,
+    } = dto.into_inner();
+    user::service::change_password(&db, &policy, user_id, &old_password, &new_password).await?;
+    ResponseResult::success_msg("修改密码成功").into()
+}
@@ -3,4 +3,4 @@ pub mod model;
 pub mod router;
 pub mod service;
 
-pub use router::init_router;
+pub use router::{init_profile_router, init_router};
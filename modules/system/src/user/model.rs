@@ -37,7 +37,7 @@ pub struct SysUser {
     /// 账号状态 （0正常 1停用,默认 '0'）
     #[serde(default = "default_status")]
     pub status: Option<String>,
-    /// '删除标志（0代表存在 2代表删除 默认 '0'）
+    /// '删除标志（0代表存在 1代表删除 默认 '0'）
     #[serde(default = "default_del_flag")]
     pub del_flag: Option<String>,
 
@@ -64,6 +64,20 @@ pub struct SysUser {
 
     /// 备注信息
     pub remark: Option<String>,
+
+    /// TOTP 密钥（Base32，绑定确认前为待确认密钥）
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+
+    /// 双因素认证状态（0未启用 1已启用）
+    pub totp_enabled: Option<String>,
+
+    /// 所属部门ID
+    pub dept_id: Option<i32>,
+
+    /// 最后一次使用的 TOTP 时间步（防重放）
+    #[serde(skip_serializing)]
+    pub totp_last_step: Option<i64>,
 }
 
 // 非空字段的默认值（与数据库默认值保持一致）
@@ -95,7 +109,12 @@ pub struct SysUserVO {
     // #[serde(with = "time::serde::rfc3339")]
     pub create_time: Option<OffsetDateTime>,
     pub remark: Option<String>,
+    pub totp_enabled: Option<String>,
+    pub dept_id: Option<i32>,
     pub role_list: Option<Vec<SysRole>>,
+    /// 岗位ID列表（仅详情返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_ids: Option<Vec<i32>>,
 }
 
 // 从实体类转换为响应DTO
@@ -114,7 +133,10 @@ impl From<SysUser> for SysUserVO {
             login_date: user.login_date,
             create_time: user.create_time,
             remark: user.remark,
+            totp_enabled: user.totp_enabled,
+            dept_id: user.dept_id,
             role_list: None,
+            post_ids: None,
         }
     }
 }
@@ -133,7 +155,9 @@ pub struct SysUserAddDTO {
     #[serde(default = "default_status")]
     pub status: Option<String>,
     pub remark: Option<String>,
+    pub dept_id: Option<i32>,
     pub role_ids: Option<Vec<i32>>, // 关联的角色ID列表
+    pub post_ids: Option<Vec<i32>>, // 关联的岗位ID列表
 }
 
 /// 修改用户时接收前端数据的请求体  
@@ -146,7 +170,46 @@ pub struct SysUserUpdateDTO {
     pub email: Option<String>,
     pub status: Option<String>,
     pub remark: Option<String>,
+    pub dept_id: Option<i32>,
     pub role_ids: Option<Vec<i32>>,
+    /// 岗位ID列表，为空时不修改，传空数组时清空
+    pub post_ids: Option<Vec<i32>>,
+}
+
+/// 个人中心：当前用户信息及其角色、权限
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileVO {
+    pub user: SysUserVO,
+    /// 角色标识列表
+    pub roles: Vec<String>,
+    /// 权限标识列表
+    pub permissions: Vec<String>,
+}
+
+/// 个人中心：修改基本资料
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileUpdateDTO {
+    pub nick_name: String,
+    pub email: Option<String>,
+    pub phone_number: Option<String>,
+}
+
+/// 重置用户密码
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPwdDTO {
+    pub user_id: i32,
+    pub password: String,
+}
+
+/// 个人中心：修改密码
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePasswordDTO {
+    pub old_password: String,
+    pub new_password: String,
 }
 
 /// 用户列表查询的参数结构体
@@ -156,6 +219,8 @@ pub struct ListUserQuery {
     pub nick_name: Option<String>,
     pub phone_number: Option<String>,
     pub status: Option<String>,
+    /// 部门ID（包含其下级部门）
+    pub dept_id: Option<i32>,
     pub begin_time: Option<String>,
     pub end_time: Option<String>,
 }
@@ -1,13 +1,54 @@
+use common::constants::Permission;
+use framework::midddleware::require_perm;
 use salvo::Router;
 
 use crate::user::handle;
 
+/// 个人中心，需在 [`init_router`] 之前挂载，以先于 `{user_id}` 匹配
+pub fn init_profile_router() -> Router {
+    Router::with_path("user/profile")
+        .get(handle::profile)
+        .push(Router::with_path("update").post(handle::update_profile))
+        .push(Router::with_path("avatar").post(handle::update_avatar))
+        .push(Router::with_path("password").post(handle::change_password))
+}
+
 pub fn init_router() -> Router {
     Router::new()
         .path("user")
-        .push(Router::with_path("page").post(handle::page))
-        .push(Router::with_path("add").post(handle::add_user))
-        .push(Router::with_path("{user_id}").get(handle::get_detail))
-        .push(Router::with_path("/delete/{user_id}").get(handle::delete))
-        .push(Router::with_path("update").post(handle::update_user))
+        .push(
+            Router::with_path("page")
+                .hoop(require_perm(Permission::UserList))
+                .post(handle::page),
+        )
+        .push(
+            Router::with_path("add")
+                .hoop(require_perm(Permission::UserAdd))
+                .post(handle::add_user),
+        )
+        .push(
+            Router::with_path("{user_id}")
+                .hoop(require_perm(Permission::UserQuery))
+                .get(handle::get_detail),
+        )
+        .push(
+            Router::with_path("/delete/{user_id}")
+                .hoop(require_perm(Permission::UserRemove))
+                .get(handle::delete),
+        )
+        .push(
+            Router::with_path("update")
+                .hoop(require_perm(Permission::UserEdit))
+                .post(handle::update_user),
+        )
+        .push(
+            Router::with_path("approve/{user_id}")
+                .hoop(require_perm(Permission::UserEdit))
+                .get(handle::approve),
+        )
+        .push(
+            Router::with_path("reset_pwd")
+                .hoop(require_perm(Permission::UserResetPwd))
+                .post(handle::reset_pwd),
+        )
 }